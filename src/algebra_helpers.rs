@@ -1,4 +1,3 @@
-use itertools::Itertools;
 use num_traits::Num;
 use std::collections::HashMap;
 use std::ops::{self, Index, IndexMut};
@@ -6,23 +5,67 @@ use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 use std::slice::Iter;
 use std::{array, fmt};
 
+pub mod aabb;
+pub mod automaton;
+pub mod dense_grid;
+pub mod grid_iter;
+pub mod grid_parse;
+pub mod hex;
+pub mod matrix;
+pub mod regions;
+pub mod symmetry;
+pub mod topology;
+pub mod visibility;
+
+pub use aabb::Aabb;
+pub use automaton::{Automaton, Growth, Neighbourhood};
+pub use dense_grid::DenseGrid;
+pub use grid_iter::{Order, PointGridIterator, ShellIterator};
+pub use grid_parse::GridParseError;
+pub use hex::{Hex, HexDirection};
+pub use matrix::Matrix;
+pub use regions::{Connectivity, Region};
+pub use symmetry::Symmetry;
+pub use topology::{EdgeMapping, Topology};
+pub use visibility::VisibilityGraph;
+
 // Taken and adapted from MIT-licensed code library lina: https://github.com/LukasKalbertodt/lina
 
-pub trait Scalar:
-    Num + Clone + Copy + fmt::Debug + AddAssign + SubAssign + MulAssign + DivAssign
-{
-}
+// Not Copy, so heap backed numbers like BigInt and Rational<BigInt> fit too.
+// Points of them support arithmetic, most geometry and the matrices ask for
+// `Scalar + Copy`.
+pub trait Scalar: Num + Clone + fmt::Debug + AddAssign + SubAssign + MulAssign + DivAssign {}
 
 impl<T> Scalar for T where
-    T: Num + Clone + Copy + fmt::Debug + AddAssign + SubAssign + MulAssign + DivAssign
+    T: Num + Clone + fmt::Debug + AddAssign + SubAssign + MulAssign + DivAssign
 {
 }
 
+// Scalars that can be divided by any non-zero element: floats, rationals
+// and ModInt. A ModInt is only a field for a prime modulus, for a composite
+// one `inverse` is None for the zero divisors.
+pub trait Field: Scalar + Copy {
+    fn inverse(&self) -> Option<Self>;
+}
+
+impl Field for f32 {
+    fn inverse(&self) -> Option<Self> {
+        (*self != 0.0).then(|| self.recip())
+    }
+}
+
+impl Field for f64 {
+    fn inverse(&self) -> Option<Self> {
+        (*self != 0.0).then(|| self.recip())
+    }
+}
+
 pub trait Float: Scalar + num_traits::Float + num_traits::FloatConst {}
 
-impl<T> Float for T where T: Scalar + num_traits::Float + num_traits::FloatConst {}
+impl<T> Float for T where T: Scalar + Copy + num_traits::Float + num_traits::FloatConst {}
 
 #[repr(transparent)]
+#[derive(Clone, Copy)]
 pub struct Point<T: Scalar, const N: usize>(pub [T; N]);
 
 pub type Point2<T> = Point<T, 2>;
@@ -31,7 +74,9 @@ impl<T: Scalar> Point<T, 2> {
     pub fn new(x: T, y: T) -> Self {
         Self([x, y])
     }
+}
 
+impl<T: Scalar + Copy> Point<T, 2> {
     pub fn get_point_in_direction(&self, direction: &Point2Direction, distance: T) -> Self {
         match direction {
             Point2Direction::North => Self::new(self.0[0], self.0[1] - distance),
@@ -46,7 +91,7 @@ impl<T: Scalar> Point<T, 2> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Point2Direction {
     North,
     NorthEast,
@@ -85,24 +130,22 @@ impl Point2Direction {
         D.iter()
     }
 
+    // turns by 90 degrees, diagonals stay diagonals
     pub fn direction_left(&self) -> Self {
-        match self {
-            Self::North => Self::West,
-            Self::East => Self::North,
-            Self::South => Self::East,
-            Self::West => Self::South,
-            _ => unimplemented!(),
-        }
+        self.turned_by_eighths(-2)
     }
 
     pub fn direction_right(&self) -> Self {
-        match self {
-            Self::North => Self::East,
-            Self::East => Self::South,
-            Self::South => Self::West,
-            Self::West => Self::North,
-            _ => unimplemented!(),
-        }
+        self.turned_by_eighths(2)
+    }
+
+    // clockwise in steps of 45 degrees
+    pub fn turned_by_eighths(&self, eighths: isize) -> Self {
+        let index = Self::all_with_diagonals().position(|d| d == self).unwrap() as isize;
+        Self::all_with_diagonals()
+            .nth((index + eighths).rem_euclid(8) as usize)
+            .copied()
+            .unwrap()
     }
 }
 
@@ -135,14 +178,23 @@ impl<T: Scalar, const N: usize> Point<T, N> {
         Self::zero()
     }
 
-    pub fn filled(value: T) -> Self {
-        std::array::from_fn(|_| value).into()
-    }
-
     pub fn unit_in_dimension(dimension: usize) -> Self {
         std::array::from_fn(|i| if i == dimension { T::one() } else { T::zero() }).into()
     }
 
+    pub fn dot(&self, other: &Self) -> T {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+    }
+}
+
+impl<T: Scalar + Copy, const N: usize> Point<T, N> {
+    pub fn filled(value: T) -> Self {
+        std::array::from_fn(|_| value).into()
+    }
+
     pub fn unit_vectors() -> Vec<Self> {
         (0..N).map(|i| Self::unit_in_dimension(i)).collect()
     }
@@ -151,7 +203,7 @@ impl<T: Scalar, const N: usize> Point<T, N> {
         Self::unit_vectors()
             .into_iter()
             .map(|p| Point::zero() - p)
-            .chain(Self::unit_vectors().into_iter())
+            .chain(Self::unit_vectors())
             .collect()
     }
 
@@ -187,8 +239,19 @@ impl<T: Scalar, const N: usize> Point<T, N> {
         (self - other).length_euclid()
     }
 
-    pub fn distance_manhattan_from(self, other: Self) -> T {
-        (self - other).length_manhattan()
+    pub fn distance_manhattan_from(self, other: Self) -> T
+    where
+        T: PartialOrd,
+    {
+        (0..N).fold(T::zero(), |acc, i| acc + abs_diff(self[i], other[i]))
+    }
+
+    // number of king moves between the points
+    pub fn distance_chebyshev_from(self, other: Self) -> T
+    where
+        T: PartialOrd,
+    {
+        (0..N).fold(T::zero(), |acc, i| max_of(acc, abs_diff(self[i], other[i])))
     }
 
     pub fn length_euclid_squared(&self) -> T {
@@ -205,8 +268,58 @@ impl<T: Scalar, const N: usize> Point<T, N> {
         self.length_euclid_squared().sqrt()
     }
 
-    pub fn length_manhattan(&self) -> T {
-        self.0.iter().fold(T::zero(), |acc, e| acc + *e)
+    pub fn length_manhattan(&self) -> T
+    where
+        T: PartialOrd,
+    {
+        self.distance_manhattan_from(Self::zero())
+    }
+
+    pub fn length_chebyshev(&self) -> T
+    where
+        T: PartialOrd,
+    {
+        self.distance_chebyshev_from(Self::zero())
+    }
+
+    // componentwise minimum
+    pub fn min(self, other: Self) -> Self
+    where
+        T: PartialOrd,
+    {
+        array::from_fn(|i| min_of(self[i], other[i])).into()
+    }
+
+    // componentwise maximum
+    pub fn max(self, other: Self) -> Self
+    where
+        T: PartialOrd,
+    {
+        array::from_fn(|i| max_of(self[i], other[i])).into()
+    }
+
+    pub fn abs(&self) -> Self
+    where
+        T: PartialOrd,
+    {
+        array::from_fn(|i| abs_diff(self[i], T::zero())).into()
+    }
+
+    // componentwise -1, 0 or 1
+    pub fn signum(&self) -> Self
+    where
+        T: PartialOrd,
+    {
+        array::from_fn(|i| {
+            if self[i] > T::zero() {
+                T::one()
+            } else if self[i] < T::zero() {
+                T::zero() - T::one()
+            } else {
+                T::zero()
+            }
+        })
+        .into()
     }
 
     pub fn vec_to(self, other: Self) -> Point<T, N> {
@@ -214,6 +327,31 @@ impl<T: Scalar, const N: usize> Point<T, N> {
     }
 }
 
+// Only needs PartialOrd, so these work for unsigned scalars and floats too.
+fn abs_diff<T: Scalar + Copy + PartialOrd>(a: T, b: T) -> T {
+    if a < b {
+        b - a
+    } else {
+        a - b
+    }
+}
+
+fn min_of<T: Scalar + Copy + PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max_of<T: Scalar + Copy + PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
 impl<T: Scalar, const N: usize> From<Point<T, N>> for [T; N] {
     fn from(value: Point<T, N>) -> Self {
         value.0
@@ -243,7 +381,7 @@ impl<T: Scalar, const N: usize> fmt::Debug for Point<T, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Point")?;
         write!(f, "[")?;
-        for (i, e) in self.0.into_iter().enumerate() {
+        for (i, e) in self.0.iter().enumerate() {
             if i != 0 {
                 write!(f, ", ")?;
             }
@@ -257,7 +395,7 @@ impl<T: Scalar, const N: usize> fmt::Display for Point<T, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Point")?;
         write!(f, "[")?;
-        for (i, e) in self.0.into_iter().enumerate() {
+        for (i, e) in self.0.iter().enumerate() {
             if i != 0 {
                 write!(f, ", ")?;
             }
@@ -269,8 +407,9 @@ impl<T: Scalar, const N: usize> fmt::Display for Point<T, N> {
 
 impl<T: Scalar, const N: usize> ops::Add<Point<T, N>> for Point<T, N> {
     type Output = Self;
-    fn add(self, rhs: Point<T, N>) -> Self::Output {
-        array::from_fn(|i| self[i] + rhs[i]).into()
+    fn add(mut self, rhs: Point<T, N>) -> Self::Output {
+        self += rhs;
+        self
     }
 }
 
@@ -292,15 +431,37 @@ impl<T: Scalar, const N: usize> ops::SubAssign<Point<T, N>> for Point<T, N> {
 
 impl<T: Scalar, const N: usize> ops::Sub<Self> for Point<T, N> {
     type Output = Point<T, N>;
-    fn sub(self, rhs: Self) -> Self::Output {
-        array::from_fn(|i| self[i] - rhs[i]).into()
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
     }
 }
 
 impl<T: Scalar, const N: usize> ops::Mul<T> for Point<T, N> {
     type Output = Point<T, N>;
     fn mul(self, rhs: T) -> Self::Output {
-        array::from_fn(|i| self[i] * rhs).into()
+        Point(self.0.map(|c| c * rhs.clone()))
+    }
+}
+
+impl<T: Scalar, const N: usize> ops::Div<T> for Point<T, N> {
+    type Output = Point<T, N>;
+    fn div(self, rhs: T) -> Self::Output {
+        Point(self.0.map(|c| c / rhs.clone()))
+    }
+}
+
+impl<T: Scalar, const N: usize> ops::Rem<T> for Point<T, N> {
+    type Output = Point<T, N>;
+    fn rem(self, rhs: T) -> Self::Output {
+        Point(self.0.map(|c| c % rhs.clone()))
+    }
+}
+
+impl<T: Scalar + ops::Neg<Output = T>, const N: usize> ops::Neg for Point<T, N> {
+    type Output = Point<T, N>;
+    fn neg(self) -> Self::Output {
+        Point(self.0.map(|c| -c))
     }
 }
 
@@ -317,68 +478,166 @@ impl<T: Scalar, const N: usize> PartialEq for Point<T, N> {
 }
 impl<T: Scalar + Eq, const N: usize> Eq for Point<T, N> {}
 
-impl<T: Scalar, const N: usize> Clone for Point<T, N> {
-    fn clone(&self) -> Self {
-        Self(self.0)
-    }
-}
-
-impl<T: Scalar, const N: usize> Copy for Point<T, N> {}
-
+// Sparse grid, only the points that were inserted exist. Keeps track of the
+// box around its points while inserting, so asking for the dimensions doesn't
+// have to look at every point.
 #[derive(Debug, Clone)]
-pub struct PointGrid<T: Scalar, const N: usize, U>(pub HashMap<Point<T, N>, U>);
+pub struct PointGrid<T: Scalar, const N: usize, U> {
+    cells: HashMap<Point<T, N>, U>,
+    bounds: Option<Aabb<T, N>>,
+    // set when a point on the border was removed, the box may be too large
+    // until it is recomputed
+    loose: bool,
+}
 
-impl<T: Scalar, const N: usize, U> Default for PointGrid<T, N, U> {
+impl<T: Scalar + Copy, const N: usize, U> Default for PointGrid<T, N, U> {
     fn default() -> Self {
-        Self(HashMap::new())
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+            loose: false,
+        }
     }
 }
 
-impl<T: Scalar + Ord, const N: usize, U> PointGrid<T, N, U> {
+impl<T: Scalar + Copy + PartialOrd + num_traits::NumCast, const N: usize, U> PointGrid<T, N, U> {
     pub fn iter_full_bounds(&self) -> PointGridIterator<T, N> {
-        let (min, max) = self.dimensions();
-        PointGridIterator::new(min, max + Point::filled(T::one()))
+        match self.dimensions() {
+            Some(bounds) => bounds.iter(),
+            None => PointGridIterator::new(Point::zero(), Point::zero()),
+        }
     }
 }
 
-impl<T: Scalar + std::hash::Hash + Eq, const N: usize, U> PointGrid<T, N, U> {
-    pub fn insert(&mut self, p: Point<T, N>, value: U) {
-        self.0.insert(p, value);
+impl<T: Scalar + Copy + PartialOrd, const N: usize, U> PointGrid<T, N, U> {
+    // The box around all points, None for an empty grid. Only has to look
+    // at every point after border points were removed without calling
+    // `shrink_bounds`.
+    pub fn dimensions(&self) -> Option<Aabb<T, N>> {
+        if self.loose {
+            Aabb::from_points(self.cells.keys().copied())
+        } else {
+            self.bounds
+        }
+    }
+
+    // recomputes the box after removals, so `dimensions` is cheap again
+    pub fn shrink_bounds(&mut self) {
+        if self.loose {
+            self.bounds = Aabb::from_points(self.cells.keys().copied());
+            self.loose = false;
+        }
+    }
+}
+
+impl<T: Scalar + Copy, const N: usize, U> PointGrid<T, N, U> {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Point<T, N>, &U)> {
+        self.cells.iter()
     }
 
+    pub fn keys(&self) -> impl Iterator<Item = &Point<T, N>> {
+        self.cells.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &U> {
+        self.cells.values()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut U> {
+        self.cells.values_mut()
+    }
+}
+
+impl<T: Scalar + Copy + std::hash::Hash + Eq, const N: usize, U> PointGrid<T, N, U> {
     pub fn get(&self, p: &Point<T, N>) -> Option<&U> {
-        self.0.get(p)
-    }
-}
-
-impl<T: Scalar + Ord, const N: usize, U> PointGrid<T, N, U> {
-    pub fn dimensions(&self) -> (Point<T, N>, Point<T, N>) {
-        (
-            Point {
-                0: (0..N)
-                    .map(|n| self.0.keys().map(|p| *p.0.get(n).unwrap()).min().unwrap())
-                    .into_iter()
-                    .collect_vec()
-                    .try_into()
-                    .unwrap(),
-            },
-            Point {
-                0: (0..N)
-                    .map(|n| self.0.keys().map(|p| *p.0.get(n).unwrap()).max().unwrap())
-                    .into_iter()
-                    .collect_vec()
-                    .try_into()
-                    .unwrap(),
-            },
-        )
-    }
-}
-
-impl<T: Scalar + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::Display
+        self.cells.get(p)
+    }
+
+    pub fn get_mut(&mut self, p: &Point<T, N>) -> Option<&mut U> {
+        self.cells.get_mut(p)
+    }
+
+    pub fn contains(&self, p: &Point<T, N>) -> bool {
+        self.cells.contains_key(p)
+    }
+}
+
+impl<T: Scalar + Copy + std::hash::Hash + Eq + PartialOrd, const N: usize, U> PointGrid<T, N, U> {
+    pub fn insert(&mut self, p: Point<T, N>, value: U) -> Option<U> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.include(p),
+            None => self.bounds = Some(Aabb::from_point(p)),
+        }
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: &Point<T, N>) -> Option<U> {
+        let result = self.cells.remove(p);
+        if result.is_some() {
+            if self.cells.is_empty() {
+                self.bounds = None;
+                self.loose = false;
+            } else if self.bounds.is_some_and(|b| b.on_border(p)) {
+                self.loose = true;
+            }
+        }
+        result
+    }
+}
+
+impl<T: Scalar + Copy + std::hash::Hash + Eq + PartialOrd, const N: usize, U>
+    FromIterator<(Point<T, N>, U)> for PointGrid<T, N, U>
+{
+    fn from_iter<I: IntoIterator<Item = (Point<T, N>, U)>>(iter: I) -> Self {
+        let mut result = Self::default();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T: Scalar + Copy + std::hash::Hash + Eq + PartialOrd, const N: usize, U>
+    Extend<(Point<T, N>, U)> for PointGrid<T, N, U>
+{
+    fn extend<I: IntoIterator<Item = (Point<T, N>, U)>>(&mut self, iter: I) {
+        for (p, u) in iter {
+            self.insert(p, u);
+        }
+    }
+}
+
+impl<T: Scalar + Copy, const N: usize, U> IntoIterator for PointGrid<T, N, U> {
+    type Item = (Point<T, N>, U);
+    type IntoIter = std::collections::hash_map::IntoIter<Point<T, N>, U>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+// same points with the same values, the tracked box doesn't matter
+impl<T: Scalar + Copy + std::hash::Hash + Eq, const N: usize, U: PartialEq> PartialEq
+    for PointGrid<T, N, U>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<T: Scalar + Copy + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::Display
     for PointGrid<T, 2, U>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (min, max) = self.dimensions();
+        let Some(Aabb { min, max }) = self.dimensions() else {
+            return writeln!(f, "Grid (empty)");
+        };
         writeln!(f, "Grid ({}, {}):", min, max)?;
         for y in min.0[1]..(max.0[1] + T::one()) {
             for x in min.0[0]..(max.0[0] + T::one()) {
@@ -394,11 +653,13 @@ impl<T: Scalar + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::
     }
 }
 
-impl<T: Scalar + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::Display
+impl<T: Scalar + Copy + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::Display
     for PointGrid<T, 3, U>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (min, max) = self.dimensions();
+        let Some(Aabb { min, max }) = self.dimensions() else {
+            return writeln!(f, "Grid (empty)");
+        };
         writeln!(f, "Grid ({}, {}):", min, max)?;
         for z in min.0[2]..(max.0[2] + T::one()) {
             for y in min.0[1]..(max.0[1] + T::one()) {
@@ -416,11 +677,13 @@ impl<T: Scalar + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::
     }
 }
 
-impl<T: Scalar + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::Display
+impl<T: Scalar + Copy + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::Display
     for PointGrid<T, 4, U>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (min, max) = self.dimensions();
+        let Some(Aabb { min, max }) = self.dimensions() else {
+            return writeln!(f, "Grid (empty)");
+        };
         writeln!(f, "Grid ({}, {}):", min, max)?;
         for w in min.0[3]..(max.0[3] + T::one()) {
             for z in min.0[2]..(max.0[2] + T::one()) {
@@ -440,47 +703,10 @@ impl<T: Scalar + Ord + std::iter::Step + std::hash::Hash, U: fmt::Display> fmt::
     }
 }
 
-pub struct PointGridIterator<T: Scalar, const N: usize> {
-    lower_bound: Point<T, N>,
-    upper_bound: Point<T, N>,
-    last: Point<T, N>,
-}
-
-impl<T: Scalar + PartialOrd, const N: usize> PointGridIterator<T, N> {
-    pub fn new(lower_bound: Point<T, N>, upper_bound: Point<T, N>) -> Self {
-        Self {
-            lower_bound,
-            upper_bound,
-            last: lower_bound,
-        }
-    }
-}
-
-impl<T: Scalar + PartialOrd, const N: usize> Iterator for PointGridIterator<T, N> {
-    type Item = Point<T, N>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.last == self.upper_bound {
-            return None;
-        }
-
-        let result = self.last;
-        for n in (0..N).rev() {
-            if self.last.0[n] + T::one() >= self.upper_bound.0[n] {
-                self.last.0[n] = self.lower_bound.0[n];
-            } else {
-                self.last.0[n] += T::one();
-                return Some(result);
-            }
-        }
-        self.last = self.upper_bound;
-        Some(result)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
 
     #[test]
     fn test_directions() {
@@ -554,6 +780,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_direction_turns() {
+        use Point2Direction::*;
+        assert_eq!(North.direction_left(), West);
+        assert_eq!(West.direction_right(), North);
+        assert_eq!(NorthEast.direction_right(), SouthEast);
+        assert_eq!(NorthWest.direction_left(), SouthWest);
+        assert_eq!(South.turned_by_eighths(-1), SouthEast);
+        assert_eq!(East.turned_by_eighths(12), West);
+    }
+
+    #[test]
+    fn test_distances() {
+        let p = Point2::new(-3, 4);
+        assert_eq!(p.length_manhattan(), 7);
+        assert_eq!(p.length_chebyshev(), 4);
+        assert_eq!(p.length_euclid_squared(), 25);
+        let q = Point3::new(2, -1, -5);
+        let r = Point3::new(-4, 1, -3);
+        assert_eq!(q.distance_manhattan_from(r), 10);
+        assert_eq!(r.distance_manhattan_from(q), 10);
+        assert_eq!(q.distance_chebyshev_from(r), 6);
+        assert_eq!(q.distance_euclid_squared_from(r), 44);
+        // unsigned coordinates work in both directions as well
+        assert_eq!(
+            Point2::<u32>::new(1, 7).distance_manhattan_from(Point2::new(4, 2)),
+            8
+        );
+        assert_eq!(
+            Point2::new(3.0, -4.0).distance_euclid_from(Point2::zero()),
+            5.0
+        );
+    }
+
+    #[test]
+    fn test_componentwise() {
+        let p = Point3::new(-3, 0, 7);
+        let q = Point3::new(2, -5, 7);
+        assert_eq!(p.dot(&q), 43);
+        assert_eq!(p.min(q), Point3::new(-3, -5, 7));
+        assert_eq!(p.max(q), Point3::new(2, 0, 7));
+        assert_eq!(p.abs(), Point3::new(3, 0, 7));
+        assert_eq!(p.signum(), Point3::new(-1, 0, 1));
+        assert_eq!(-p, Point3::new(3, 0, -7));
+        assert_eq!(p / 2, Point3::new(-1, 0, 3));
+        assert_eq!(p % 2, Point3::new(-1, 0, 1));
+    }
+
     #[test]
     fn test_point_grid_dimensions() {
         let mut pg: PointGrid<isize, 2, bool> = PointGrid::default();
@@ -562,8 +836,28 @@ mod tests {
         pg.insert(Point2::new(20, -10), true);
         assert_eq!(
             pg.dimensions(),
-            (Point2::new(-20, -10), Point2::new(20, 20))
+            Some(Aabb::new(Point2::new(-20, -10), Point2::new(20, 20)))
+        );
+        assert_eq!(pg.iter_full_bounds().count(), 41 * 31);
+
+        // removing an inner point keeps the box, a border point shrinks it
+        pg.remove(&Point2::new(0, 0));
+        assert_eq!(pg.dimensions().unwrap().volume(), 41 * 31);
+        pg.remove(&Point2::new(-20, 20));
+        assert_eq!(
+            pg.dimensions(),
+            Some(Aabb::from_point(Point2::new(20, -10)))
+        );
+        pg.shrink_bounds();
+        assert_eq!(
+            pg.dimensions(),
+            Some(Aabb::from_point(Point2::new(20, -10)))
         );
+        pg.remove(&Point2::new(20, -10));
+        assert_eq!(pg.dimensions(), None);
+        assert!(pg.is_empty());
+        assert_eq!(pg.iter_full_bounds().count(), 0);
+        assert_eq!(pg.to_string(), "Grid (empty)\n");
     }
 
     #[test]
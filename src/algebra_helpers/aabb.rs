@@ -0,0 +1,182 @@
+use num_traits::NumCast;
+use std::fmt;
+
+use super::{Point, PointGridIterator, Scalar, ShellIterator};
+
+// Axis aligned box with inclusive corners, so a box always holds at least
+// one point. Every component of `min` is at most the one of `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb<T: Scalar, const N: usize> {
+    pub min: Point<T, N>,
+    pub max: Point<T, N>,
+}
+
+impl<T: Scalar + Copy + PartialOrd, const N: usize> Aabb<T, N> {
+    // the smallest box holding both corners, in any order
+    pub fn new(a: Point<T, N>, b: Point<T, N>) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    pub fn from_point(p: Point<T, N>) -> Self {
+        Self { min: p, max: p }
+    }
+
+    // None for no points at all
+    pub fn from_points(points: impl IntoIterator<Item = Point<T, N>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut result = Self::from_point(points.next()?);
+        for p in points {
+            result.include(p);
+        }
+        Some(result)
+    }
+
+    pub fn contains(&self, p: &Point<T, N>) -> bool {
+        (0..N).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    // the points in both boxes, None if they don't overlap
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        (0..N)
+            .all(|i| min[i] <= max[i])
+            .then_some(Self { min, max })
+    }
+
+    // the smallest box holding both
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    // grows the box just enough to hold p
+    pub fn include(&mut self, p: Point<T, N>) {
+        self.min = self.min.min(p);
+        self.max = self.max.max(p);
+    }
+
+    // grows the box by `margin` on every side, e.g. by one to cover all
+    // neighbours of the points inside
+    pub fn expand(&self, margin: T) -> Self {
+        let margin = Point::filled(margin);
+        Self {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    // whether p lies on the surface of the box
+    pub fn on_border(&self, p: &Point<T, N>) -> bool {
+        (0..N).any(|i| p[i] == self.min[i] || p[i] == self.max[i])
+    }
+
+    // number of points along each axis
+    pub fn size(&self) -> Point<T, N> {
+        self.max - self.min + Point::filled(T::one())
+    }
+
+    // number of points inside
+    pub fn volume(&self) -> T {
+        self.size().0.into_iter().fold(T::one(), |acc, s| acc * s)
+    }
+
+    // the points on the surface
+    pub fn border(&self) -> ShellIterator<T, N> {
+        self.shell(T::one())
+    }
+
+    // the points less than `thickness` steps inside the surface
+    pub fn shell(&self, thickness: T) -> ShellIterator<T, N> {
+        ShellIterator::new(self.min, self.max, thickness)
+    }
+}
+
+impl<T: Scalar + Copy + PartialOrd + NumCast, const N: usize> Aabb<T, N> {
+    pub fn iter(&self) -> PointGridIterator<T, N> {
+        PointGridIterator::new(self.min, self.max + Point::filled(T::one()))
+    }
+}
+
+impl<T: Scalar + Copy + PartialOrd + NumCast, const N: usize> IntoIterator for Aabb<T, N> {
+    type Item = Point<T, N>;
+    type IntoIter = PointGridIterator<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Scalar + Copy, const N: usize> fmt::Display for Aabb<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::{Point2, Point3};
+
+    #[test]
+    fn test_set_operations() {
+        let a = Aabb::new(Point2::new(4, 0), Point2::new(0, 2));
+        assert_eq!(a.min, Point2::new(0, 0));
+        assert_eq!(a.max, Point2::new(4, 2));
+        let b = Aabb::new(Point2::new(3, 1), Point2::new(6, 5));
+
+        assert_eq!(
+            a.intersect(&b),
+            Some(Aabb::new(Point2::new(3, 1), Point2::new(4, 2)))
+        );
+        assert_eq!(a.intersect(&b), b.intersect(&a));
+        assert_eq!(a.union(&b), Aabb::new(Point2::new(0, 0), Point2::new(6, 5)));
+        assert!(a.union(&b).contains_box(&a));
+        assert!(!a.contains_box(&b));
+
+        // touching at a corner still shares that point
+        let c = Aabb::from_point(Point2::new(6, 5));
+        assert_eq!(b.intersect(&c), Some(c));
+        assert_eq!(a.intersect(&c), None);
+    }
+
+    #[test]
+    fn test_include_and_expand() {
+        let points = [Point3::new(1, -2, 0), Point3::new(-1, 3, 0)];
+        let mut aabb = Aabb::from_points(points).unwrap();
+        assert_eq!(aabb.size(), Point3::new(3, 6, 1));
+        assert_eq!(aabb.volume(), 18);
+        assert!(aabb.on_border(&Point3::new(0, 0, 0)));
+
+        aabb.include(Point3::new(0, 0, 4));
+        assert_eq!(aabb.volume(), 90);
+        assert!(aabb.contains(&Point3::new(0, 0, 2)));
+        assert!(!aabb.contains(&Point3::new(0, 0, 5)));
+        assert!(!aabb.on_border(&Point3::new(0, 0, 2)));
+
+        let grown = aabb.expand(1);
+        assert_eq!(grown.volume(), 5 * 8 * 7);
+        assert!(grown.contains_box(&aabb));
+        assert_eq!(Aabb::<isize, 2>::from_points([]), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let aabb = Aabb::new(Point2::new(-1, 5), Point2::new(1, 6));
+        let points = aabb.iter().collect::<Vec<_>>();
+        assert_eq!(points.len() as isize, aabb.volume());
+        assert!(points.iter().all(|p| aabb.contains(p)));
+        assert_eq!(points[0], aabb.min);
+        assert_eq!(points[5], aabb.max);
+        assert_eq!(aabb.to_string(), "Point[-1, 5], Point[1, 6]");
+    }
+}
@@ -0,0 +1,477 @@
+use std::collections::{HashMap, HashSet};
+use std::thread;
+
+use super::{Hex, Point, PointGrid, VisibilityGraph};
+
+// Cellular automaton on a `PointGrid<isize, N, U>`. Every step each cell gets
+// the state returned by the rule, which sees the current state of the cell
+// and the states of its neighbours.
+//
+// Only cells whose neighbourhood changed in the last step are recomputed,
+// everything else can't change. Large steps are spread over several threads.
+
+// Which cells count as neighbours of a cell.
+pub enum Neighbourhood<U, const N: usize> {
+    // the cells at the given offsets
+    Offsets(Vec<Point<isize, N>>),
+    // The first cell in each direction that isn't `transparent`, e.g. the
+    // first seat seen across the floor. Looks no further than the grid.
+    LineOfSight {
+        directions: Vec<Point<isize, N>>,
+        transparent: fn(&U) -> bool,
+    },
+    // explicit neighbours per cell, see `Neighbourhood::graph`
+    Graph {
+        neighbours: VisibilityGraph<isize, N>,
+        dependents: VisibilityGraph<isize, N>,
+    },
+}
+
+impl<U, const N: usize> Neighbourhood<U, N> {
+    // cells sharing a face
+    pub fn von_neumann() -> Self {
+        Self::Offsets(Point::directions())
+    }
+
+    // cells sharing a face, edge or corner
+    pub fn moore() -> Self {
+        Self::Offsets(Point::directions_with_diagonals())
+    }
+
+    pub fn line_of_sight(transparent: fn(&U) -> bool) -> Self {
+        Self::LineOfSight {
+            directions: Point::directions_with_diagonals(),
+            transparent,
+        }
+    }
+
+    // Fixed neighbours for every cell, e.g. a precomputed
+    // `PointGrid::visibility_graph` instead of `line_of_sight`.
+    pub fn graph(neighbours: VisibilityGraph<isize, N>) -> Self {
+        let mut dependents: VisibilityGraph<isize, N> = HashMap::new();
+        for (p, qs) in &neighbours {
+            for q in qs {
+                dependents.entry(*q).or_default().push(*p);
+            }
+        }
+        Self::Graph {
+            neighbours,
+            dependents,
+        }
+    }
+
+    fn neighbours<'a>(
+        &self,
+        grid: &'a PointGrid<isize, N, U>,
+        p: Point<isize, N>,
+        background: Option<&'a U>,
+        result: &mut Vec<&'a U>,
+    ) {
+        result.clear();
+        match self {
+            Self::Offsets(offsets) => {
+                result.extend(
+                    offsets
+                        .iter()
+                        .filter_map(|d| grid.get(&(p + *d)).or(background)),
+                );
+            }
+            Self::LineOfSight {
+                directions,
+                transparent,
+            } => {
+                result.extend(
+                    directions
+                        .iter()
+                        .filter_map(|d| grid.first_visible(p, *d, transparent))
+                        .map(|(_, u)| u),
+                );
+            }
+            Self::Graph { neighbours, .. } => {
+                result.extend(
+                    neighbours
+                        .get(&p)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|q| grid.get(q).or(background)),
+                );
+            }
+        }
+    }
+
+    // the cells that have `p` as a neighbour
+    fn dependents(
+        &self,
+        grid: &PointGrid<isize, N, U>,
+        p: Point<isize, N>,
+    ) -> Vec<Point<isize, N>> {
+        match self {
+            Self::Offsets(offsets) => offsets.iter().map(|d| p - *d).collect(),
+            Self::LineOfSight {
+                directions,
+                transparent,
+            } => {
+                // everything up to the first opaque cell looking back sees p,
+                // the transparent cells in between as well
+                let mut result = vec![];
+                for d in directions {
+                    for (q, u) in grid.ray(p, Point::zero() - *d) {
+                        result.push(q);
+                        if !transparent(u) {
+                            break;
+                        }
+                    }
+                }
+                result
+            }
+            Self::Graph { dependents, .. } => dependents.get(&p).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+impl<U> Neighbourhood<U, 2> {
+    // the six neighbours of hex cells in axial coordinates, see `Hex`
+    pub fn hex() -> Self {
+        Self::Offsets(Hex::neighbour_offsets())
+    }
+}
+
+// Which cells take part in the automaton.
+pub enum Growth<U, const N: usize> {
+    // only the cells that are in the grid at the start
+    Fixed,
+    // All cells inside the box (inclusive). Cells that are not stored in the
+    // grid are in the background state.
+    Bounded {
+        min: Point<isize, N>,
+        max: Point<isize, N>,
+        background: U,
+    },
+    // Every cell, the grid only stores the cells that are not in the
+    // background state. A background cell surrounded by background cells
+    // has to stay in the background.
+    Unbounded {
+        background: U,
+    },
+}
+
+// steps with fewer cells to update are not worth spawning threads for
+const PARALLEL_THRESHOLD: usize = 4096;
+
+pub struct Automaton<U, const N: usize, R> {
+    grid: PointGrid<isize, N, U>,
+    neighbourhood: Neighbourhood<U, N>,
+    growth: Growth<U, N>,
+    rule: R,
+    threads: usize,
+    // cells that might change in the next step
+    active: HashSet<Point<isize, N>>,
+}
+
+impl<U, const N: usize, R> Automaton<U, N, R>
+where
+    U: Clone + PartialEq + Send + Sync,
+    R: Fn(&U, &[&U]) -> U + Sync,
+{
+    pub fn new(
+        grid: PointGrid<isize, N, U>,
+        neighbourhood: Neighbourhood<U, N>,
+        growth: Growth<U, N>,
+        rule: R,
+    ) -> Self {
+        let mut result = Self {
+            grid,
+            neighbourhood,
+            growth,
+            rule,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            active: HashSet::new(),
+        };
+        let all = match result.growth {
+            Growth::Fixed => result.grid.keys().copied().collect::<Vec<_>>(),
+            Growth::Bounded { min, max, .. } => {
+                super::PointGridIterator::new(min, max + Point::filled(1)).collect()
+            }
+            Growth::Unbounded { .. } => result.grid.keys().copied().collect(),
+        };
+        result.mark_active(&all);
+        result
+    }
+
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    pub fn grid(&self) -> &PointGrid<isize, N, U> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> PointGrid<isize, N, U> {
+        self.grid
+    }
+
+    fn background(&self) -> Option<&U> {
+        match &self.growth {
+            Growth::Fixed => None,
+            Growth::Bounded { background, .. } | Growth::Unbounded { background } => {
+                Some(background)
+            }
+        }
+    }
+
+    fn is_cell(&self, p: &Point<isize, N>) -> bool {
+        match &self.growth {
+            Growth::Fixed => self.grid.contains(p),
+            Growth::Bounded { min, max, .. } => (0..N).all(|n| min[n] <= p[n] && p[n] <= max[n]),
+            Growth::Unbounded { .. } => true,
+        }
+    }
+
+    // marks the changed cells and everything that sees them
+    fn mark_active(&mut self, changed: &[Point<isize, N>]) {
+        let mut active = HashSet::new();
+        for p in changed {
+            active.insert(*p);
+            active.extend(self.neighbourhood.dependents(&self.grid, *p));
+        }
+        active.retain(|p| self.is_cell(p));
+        self.active = active;
+    }
+
+    // new states of the given cells, only those that differ from the current
+    fn compute(&self, cells: &[Point<isize, N>]) -> Vec<(Point<isize, N>, U)> {
+        let background = self.background();
+        let mut neighbours = vec![];
+        cells
+            .iter()
+            .filter_map(|p| {
+                let current = self.grid.get(p).or(background)?;
+                self.neighbourhood
+                    .neighbours(&self.grid, *p, background, &mut neighbours);
+                let next = (self.rule)(current, &neighbours);
+                (next != *current).then_some((*p, next))
+            })
+            .collect()
+    }
+
+    // Advances by one step, returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let cells = self.active.iter().copied().collect::<Vec<_>>();
+        let changes = if self.threads > 1 && cells.len() >= PARALLEL_THRESHOLD {
+            let chunk_size = cells.len().div_ceil(self.threads);
+            thread::scope(|s| {
+                let handles = cells
+                    .chunks(chunk_size)
+                    .map(|chunk| s.spawn(|| self.compute(chunk)))
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .flat_map(|h| h.join().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        } else {
+            self.compute(&cells)
+        };
+
+        let changed = changes.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        for (p, u) in changes {
+            if self.background() == Some(&u) {
+                self.grid.remove(&p);
+            } else {
+                self.grid.insert(p, u);
+            }
+        }
+        // no shrink_bounds here, `dimensions` recomputes loose bounds when
+        // they are asked for
+        self.mark_active(&changed);
+        !changed.is_empty()
+    }
+
+    // Steps until nothing changes anymore, returns the number of steps that
+    // changed something.
+    pub fn run_until_stable(&mut self) -> usize {
+        let mut steps = 0;
+        while self.step() {
+            steps += 1;
+        }
+        steps
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::Point2;
+
+    fn life(cell: &bool, neighbours: &[&bool]) -> bool {
+        let alive = neighbours.iter().filter(|n| ***n).count();
+        matches!((cell, alive), (true, 2) | (_, 3))
+    }
+
+    fn alive(grid: &PointGrid<isize, 2, bool>) -> Vec<Point2<isize>> {
+        let mut result = grid
+            .iter()
+            .filter(|(_, v)| **v)
+            .map(|(p, _)| *p)
+            .collect::<Vec<_>>();
+        result.sort_by_key(|p| (p[1], p[0]));
+        result
+    }
+
+    #[test]
+    fn test_unbounded_glider() {
+        let grid =
+            PointGrid::parse_slice_with(".#.\n..#\n###", Point::zero(), &['.'], |c| Some(c == '#'))
+                .unwrap();
+        let mut automaton = Automaton::new(
+            grid,
+            Neighbourhood::moore(),
+            Growth::Unbounded { background: false },
+            life,
+        );
+        automaton.run(4);
+        // a glider moves by (1, 1) every 4 steps
+        assert_eq!(
+            alive(automaton.grid()),
+            vec![
+                Point2::new(2, 1),
+                Point2::new(3, 2),
+                Point2::new(1, 3),
+                Point2::new(2, 3),
+                Point2::new(3, 3)
+            ]
+        );
+        assert_eq!(automaton.grid().len(), 5);
+    }
+
+    #[test]
+    fn test_bounded_and_fixed() {
+        // a blinker on the edge of a 3x3 box loses the cells outside
+        let grid = PointGrid::parse_slice_with("###", Point2::new(-1, 0), &[], |c| Some(c == '#'))
+            .unwrap();
+        let bounds = Growth::Bounded {
+            min: Point2::new(-1, 0),
+            max: Point2::new(1, 2),
+            background: false,
+        };
+        let mut automaton = Automaton::new(grid.clone(), Neighbourhood::moore(), bounds, life);
+        assert!(automaton.step());
+        assert_eq!(
+            alive(automaton.grid()),
+            vec![Point2::new(0, 0), Point2::new(0, 1)]
+        );
+        assert!(automaton.step());
+        assert!(!automaton.step());
+        assert!(alive(automaton.grid()).is_empty());
+
+        // only the three cells exist, the middle one has two neighbours
+        let mut automaton = Automaton::new(grid, Neighbourhood::moore(), Growth::Fixed, life);
+        assert!(automaton.step());
+        assert_eq!(alive(automaton.grid()), vec![Point2::new(0, 0)]);
+        assert_eq!(automaton.grid().len(), 3);
+    }
+
+    #[test]
+    fn test_line_of_sight() {
+        // 0 is floor, the rule counts the visible seats
+        let grid = PointGrid::parse_with("1001\n0000\n1000", |c| c.to_digit(10)).unwrap();
+        let mut automaton = Automaton::new(
+            grid,
+            Neighbourhood::line_of_sight(|u| *u == 0),
+            Growth::Fixed,
+            |u, n| if *u == 0 { 0 } else { n.len() as u32 + 1 },
+        );
+        automaton.step();
+        let grid = automaton.grid();
+        assert_eq!(grid.get(&Point2::new(0, 0)), Some(&3));
+        assert_eq!(grid.get(&Point2::new(3, 0)), Some(&2));
+        assert_eq!(grid.get(&Point2::new(0, 2)), Some(&2));
+        assert_eq!(grid.get(&Point2::new(1, 1)), Some(&0));
+    }
+
+    #[test]
+    fn test_line_of_sight_through_transparent_cells() {
+        // Even values are transparent. The 1 turns into a 3 in the first
+        // step, which the transparent cells in front of it have to notice.
+        let grid = PointGrid::parse_with("001", |c| c.to_digit(10)).unwrap();
+        let mut automaton = Automaton::new(
+            grid,
+            Neighbourhood::line_of_sight(|u| *u % 2 == 0),
+            Growth::Fixed,
+            |u: &u32, n: &[&u32]| match u {
+                1 | 3 => 3,
+                _ if n.iter().any(|v| **v == 3) => 2,
+                _ => *u,
+            },
+        );
+        assert_eq!(automaton.run_until_stable(), 2);
+        assert_eq!(automaton.grid().get(&Point2::new(0, 0)), Some(&2));
+        assert_eq!(automaton.grid().get(&Point2::new(1, 0)), Some(&2));
+    }
+
+    #[test]
+    fn test_visibility_graph() {
+        let grid = PointGrid::parse_with("1001\n0000\n1000", |c| c.to_digit(10)).unwrap();
+        let graph = grid.visibility_graph(&Point2::directions_with_diagonals(), |u| *u == 0);
+        let rule = |u: &u32, n: &[&u32]| if *u == 0 { 0 } else { n.len() as u32 + 1 };
+        let mut cached = Automaton::new(
+            grid.clone(),
+            Neighbourhood::graph(graph),
+            Growth::Fixed,
+            rule,
+        );
+        let mut scanning = Automaton::new(
+            grid,
+            Neighbourhood::line_of_sight(|u| *u == 0),
+            Growth::Fixed,
+            rule,
+        );
+        cached.run(3);
+        scanning.run(3);
+        assert_eq!(cached.grid(), scanning.grid());
+    }
+
+    #[test]
+    fn test_hex_neighbourhood() {
+        // a single cell flips its six neighbours
+        let mut grid = PointGrid::default();
+        grid.insert(Point2::new(0, 0), true);
+        let mut automaton = Automaton::new(
+            grid,
+            Neighbourhood::hex(),
+            Growth::Unbounded { background: false },
+            |_, n: &[&bool]| n.iter().filter(|b| ***b).count() == 1,
+        );
+        automaton.step();
+        assert_eq!(automaton.grid().len(), 6);
+    }
+
+    #[test]
+    fn test_threads() {
+        // something chaotic on a big grid, the result must not depend on
+        // the number of threads
+        let grid = (0..80)
+            .flat_map(|x| (0..80).map(move |y| (x, y)))
+            .filter(|(x, y)| (x * 7 + y * 13 + x * y) % 5 < 2)
+            .map(|(x, y)| (Point2::new(x, y), true))
+            .collect::<PointGrid<_, 2, _>>();
+        let run = |threads| {
+            let mut automaton = Automaton::new(
+                grid.clone(),
+                Neighbourhood::moore(),
+                Growth::Unbounded { background: false },
+                life,
+            );
+            automaton.set_threads(threads);
+            automaton.run(5);
+            alive(automaton.grid())
+        };
+        assert_eq!(run(1), run(4));
+    }
+}
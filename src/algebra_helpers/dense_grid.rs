@@ -0,0 +1,327 @@
+use std::array;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use super::{Point, Point2, PointGrid};
+
+// Grid with fixed bounds stored in a single Vec. The first dimension is the
+// fastest changing one, so for 2D grids each row (fixed y) is contiguous.
+// Lookups compute the offset directly instead of hashing the point.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenseGrid<U, const N: usize> {
+    lower_bound: Point<isize, N>,
+    size: [usize; N],
+    strides: [usize; N],
+    cells: Vec<U>,
+}
+
+impl<U, const N: usize> DenseGrid<U, N> {
+    pub fn from_fn(
+        lower_bound: Point<isize, N>,
+        size: [usize; N],
+        mut f: impl FnMut(Point<isize, N>) -> U,
+    ) -> Self {
+        let strides = Self::strides_for(size);
+        let len = size.iter().product();
+        let mut grid = Self {
+            lower_bound,
+            size,
+            strides,
+            cells: Vec::with_capacity(len),
+        };
+        for i in 0..len {
+            let p = grid.point_of(i);
+            grid.cells.push(f(p));
+        }
+        grid
+    }
+
+    fn strides_for(size: [usize; N]) -> [usize; N] {
+        let mut strides = [1; N];
+        for n in 1..N {
+            strides[n] = strides[n - 1] * size[n - 1];
+        }
+        strides
+    }
+
+    pub fn lower_bound(&self) -> Point<isize, N> {
+        self.lower_bound
+    }
+
+    // exclusive
+    pub fn upper_bound(&self) -> Point<isize, N> {
+        Point(array::from_fn(|n| {
+            self.lower_bound.0[n] + self.size[n] as isize
+        }))
+    }
+
+    pub fn size(&self) -> [usize; N] {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, p: &Point<isize, N>) -> bool {
+        self.index_of(p).is_some()
+    }
+
+    pub fn index_of(&self, p: &Point<isize, N>) -> Option<usize> {
+        let mut index = 0;
+        for n in 0..N {
+            let offset = p.0[n] - self.lower_bound.0[n];
+            if offset < 0 || offset as usize >= self.size[n] {
+                return None;
+            }
+            index += offset as usize * self.strides[n];
+        }
+        Some(index)
+    }
+
+    pub fn point_of(&self, index: usize) -> Point<isize, N> {
+        Point(array::from_fn(|n| {
+            self.lower_bound.0[n] + ((index / self.strides[n]) % self.size[n]) as isize
+        }))
+    }
+
+    pub fn get(&self, p: &Point<isize, N>) -> Option<&U> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: &Point<isize, N>) -> Option<&mut U> {
+        self.index_of(p).map(|i| &mut self.cells[i])
+    }
+
+    pub fn values(&self) -> std::slice::Iter<'_, U> {
+        self.cells.iter()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point<isize, N>> + '_ {
+        (0..self.len()).map(|i| self.point_of(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point<isize, N>, &U)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, u)| (self.point_of(i), u))
+    }
+
+    // the cells at `p + d` for each direction that stays inside the grid
+    pub fn neighbours<'a>(
+        &'a self,
+        p: &'a Point<isize, N>,
+        directions: &'a [Point<isize, N>],
+    ) -> impl Iterator<Item = (Point<isize, N>, &'a U)> + 'a {
+        directions.iter().filter_map(|d| {
+            let n = *p + *d;
+            self.get(&n).map(|u| (n, u))
+        })
+    }
+
+    pub fn map<V>(&self, mut f: impl FnMut(Point<isize, N>, &U) -> V) -> DenseGrid<V, N> {
+        DenseGrid {
+            lower_bound: self.lower_bound,
+            size: self.size,
+            strides: self.strides,
+            cells: self
+                .cells
+                .iter()
+                .enumerate()
+                .map(|(i, u)| f(self.point_of(i), u))
+                .collect(),
+        }
+    }
+}
+
+impl<U: Clone, const N: usize> DenseGrid<U, N> {
+    pub fn new(lower_bound: Point<isize, N>, size: [usize; N], fill: U) -> Self {
+        Self {
+            lower_bound,
+            size,
+            strides: Self::strides_for(size),
+            cells: vec![fill; size.iter().product()],
+        }
+    }
+
+    // Takes the bounds of the point grid, points missing from it are set to
+    // `fill`.
+    pub fn from_point_grid(grid: PointGrid<isize, N, U>, fill: U) -> Self {
+        let Some(bounds) = grid.dimensions() else {
+            return Self::new(Point::zero(), [0; N], fill);
+        };
+        let size = array::from_fn(|n| bounds.size()[n] as usize);
+        let mut result = Self::new(bounds.min, size, fill);
+        for (p, u) in grid {
+            result[p] = u;
+        }
+        result
+    }
+}
+
+impl<U> DenseGrid<U, 2> {
+    pub fn width(&self) -> usize {
+        self.size[0]
+    }
+
+    pub fn height(&self) -> usize {
+        self.size[1]
+    }
+
+    // None if y is outside of the grid, like `get`
+    pub fn row(&self, y: isize) -> Option<&[U]> {
+        let range = self.row_range(y)?;
+        Some(&self.cells[range])
+    }
+
+    pub fn row_mut(&mut self, y: isize) -> Option<&mut [U]> {
+        let range = self.row_range(y)?;
+        Some(&mut self.cells[range])
+    }
+
+    fn row_range(&self, y: isize) -> Option<std::ops::Range<usize>> {
+        let offset = y - self.lower_bound.0[1];
+        if offset < 0 || offset as usize >= self.height() {
+            return None;
+        }
+        let start = offset as usize * self.width();
+        Some(start..start + self.width())
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, U> {
+        self.cells.chunks(self.width().max(1))
+    }
+
+    pub fn column(&self, x: isize) -> Option<impl Iterator<Item = &U>> {
+        let offset = x - self.lower_bound.0[0];
+        if offset < 0 || offset as usize >= self.width() {
+            return None;
+        }
+        Some(
+            self.cells
+                .iter()
+                .skip(offset as usize)
+                .step_by(self.width()),
+        )
+    }
+}
+
+impl<U, const N: usize> From<DenseGrid<U, N>> for PointGrid<isize, N, U> {
+    fn from(value: DenseGrid<U, N>) -> Self {
+        let points = (0..value.len())
+            .map(|i| value.point_of(i))
+            .collect::<Vec<_>>();
+        points.into_iter().zip(value.cells).collect()
+    }
+}
+
+impl<U, const N: usize> Index<Point<isize, N>> for DenseGrid<U, N> {
+    type Output = U;
+
+    fn index(&self, p: Point<isize, N>) -> &Self::Output {
+        self.get(&p)
+            .unwrap_or_else(|| panic!("point {} is outside of the grid", p))
+    }
+}
+
+impl<U, const N: usize> IndexMut<Point<isize, N>> for DenseGrid<U, N> {
+    fn index_mut(&mut self, p: Point<isize, N>) -> &mut Self::Output {
+        match self.index_of(&p) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("point {} is outside of the grid", p),
+        }
+    }
+}
+
+impl<U: fmt::Display> fmt::Display for DenseGrid<U, 2> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max = self.upper_bound() - Point2::new(1, 1);
+        writeln!(f, "Grid ({}, {}):", self.lower_bound, max)?;
+        for row in self.rows() {
+            for u in row {
+                write!(f, "{}", u)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::Point3;
+
+    #[test]
+    fn test_indexing() {
+        let grid = DenseGrid::from_fn(Point2::new(-2, 1), [4, 3], |p| p.0[0] * 10 + p.0[1]);
+        assert_eq!(grid.len(), 12);
+        assert_eq!(grid.upper_bound(), Point2::new(2, 4));
+        assert_eq!(grid[Point2::new(-2, 1)], -19);
+        assert_eq!(grid[Point2::new(1, 3)], 13);
+        assert_eq!(grid.get(&Point2::new(2, 3)), None);
+        assert_eq!(grid.get(&Point2::new(0, 0)), None);
+        for (i, p) in grid.points().enumerate() {
+            assert_eq!(grid.index_of(&p), Some(i));
+        }
+
+        let grid = DenseGrid::from_fn(Point3::new(0, 0, 0), [2, 3, 4], |p| p);
+        assert!(grid.iter().all(|(p, q)| p == *q));
+        assert_eq!(grid.index_of(&Point3::new(1, 2, 3)), Some(23));
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut grid = DenseGrid::from_fn(Point2::new(0, 0), [3, 2], |p| p.0[0] + 3 * p.0[1]);
+        assert_eq!(grid.row(1), Some(&[3, 4, 5][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.row(-1), None);
+        assert_eq!(
+            grid.column(2).unwrap().copied().collect::<Vec<_>>(),
+            vec![2, 5]
+        );
+        assert!(grid.column(3).is_none());
+        grid.row_mut(0).unwrap().reverse();
+        assert!(grid.row_mut(2).is_none());
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[2, 1, 0][..], &[3, 4, 5][..]]
+        );
+        assert_eq!(
+            grid.to_string(),
+            "Grid (Point[0, 0], Point[2, 1]):\n210\n345\n"
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = DenseGrid::new(Point2::new(0, 0), [3, 3], 1);
+        let directions = Point2::directions_with_diagonals();
+        let count = |p| grid.neighbours(&p, &directions).count();
+        assert_eq!(count(Point2::new(1, 1)), 8);
+        assert_eq!(count(Point2::new(0, 1)), 5);
+        assert_eq!(count(Point2::new(2, 2)), 3);
+    }
+
+    #[test]
+    fn test_point_grid_conversion() {
+        let mut sparse = PointGrid::default();
+        sparse.insert(Point2::new(-1, 5), 'a');
+        sparse.insert(Point2::new(1, 3), 'b');
+        let dense = DenseGrid::from_point_grid(sparse, '.');
+        assert_eq!(dense.size(), [3, 3]);
+        assert_eq!(dense.row(3), Some(&['.', '.', 'b'][..]));
+        assert_eq!(dense.row(5), Some(&['a', '.', '.'][..]));
+
+        let back = PointGrid::from(dense.clone());
+        assert_eq!(back.len(), 9);
+        assert_eq!(back.get(&Point2::new(-1, 5)), Some(&'a'));
+        assert_eq!(DenseGrid::from_point_grid(back, '.'), dense);
+        assert!(DenseGrid::from_point_grid(PointGrid::<isize, 2, char>::default(), '.').is_empty());
+    }
+}
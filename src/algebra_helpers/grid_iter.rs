@@ -0,0 +1,374 @@
+use num_traits::NumCast;
+use std::iter::FusedIterator;
+
+use super::{Point, Scalar};
+
+// Which axis changes fastest when walking a box of points. Named like array
+// layouts: row-major (the default) compares points lexicographically, so the
+// last axis changes fastest. Column-major changes the first axis fastest,
+// which for `Point2` (x, y) is reading order, row by row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    #[default]
+    RowMajor,
+    ColumnMajor,
+}
+
+// All points from lower_bound (inclusive) to upper_bound (exclusive). Works
+// on indices rather than stepping a point along, so it knows its length and
+// can be walked from both ends.
+#[derive(Debug, Clone)]
+pub struct PointGridIterator<T: Scalar, const N: usize> {
+    lower_bound: Point<T, N>,
+    step: Point<T, N>,
+    order: Order,
+    // number of points along each axis
+    counts: [usize; N],
+    front: usize,
+    back: usize,
+}
+
+impl<T: Scalar + Copy + PartialOrd + NumCast, const N: usize> PointGridIterator<T, N> {
+    pub fn new(lower_bound: Point<T, N>, upper_bound: Point<T, N>) -> Self {
+        Self::with_step(lower_bound, upper_bound, Point::filled(T::one()))
+    }
+
+    // Only every step[i]-th coordinate along axis i, starting at the lower
+    // bound. Steps have to be positive.
+    pub fn with_step(
+        lower_bound: Point<T, N>,
+        upper_bound: Point<T, N>,
+        step: Point<T, N>,
+    ) -> Self {
+        let counts = std::array::from_fn(|i| {
+            assert!(step[i] > T::zero(), "step must be positive");
+            if upper_bound[i] <= lower_bound[i] {
+                return 0;
+            }
+            // rounds up, also for floats
+            let span = upper_bound[i] - lower_bound[i];
+            let count = (span / step[i]).to_usize().unwrap();
+            if lower_bound[i] + step[i] * T::from(count).unwrap() < upper_bound[i] {
+                count + 1
+            } else {
+                count
+            }
+        });
+        Self {
+            lower_bound,
+            step,
+            order: Order::RowMajor,
+            counts,
+            front: 0,
+            back: counts.iter().product(),
+        }
+    }
+
+    // the same points in another order, starts over from the beginning
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self.front = 0;
+        self.back = self.counts.iter().product();
+        self
+    }
+
+    pub fn column_major(self) -> Self {
+        self.order(Order::ColumnMajor)
+    }
+
+    fn point_at(&self, mut index: usize) -> Point<T, N> {
+        let mut result = self.lower_bound;
+        let mut place = |i: usize| {
+            let k = index % self.counts[i];
+            index /= self.counts[i];
+            result[i] += self.step[i] * T::from(k).unwrap();
+        };
+        match self.order {
+            Order::RowMajor => (0..N).rev().for_each(&mut place),
+            Order::ColumnMajor => (0..N).for_each(&mut place),
+        }
+        result
+    }
+}
+
+impl<T: Scalar + Copy + PartialOrd + NumCast, const N: usize> Iterator for PointGridIterator<T, N> {
+    type Item = Point<T, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.point_at(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<T: Scalar + Copy + PartialOrd + NumCast, const N: usize> DoubleEndedIterator
+    for PointGridIterator<T, N>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.point_at(self.back))
+    }
+}
+
+impl<T: Scalar + Copy + PartialOrd + NumCast, const N: usize> ExactSizeIterator
+    for PointGridIterator<T, N>
+{
+}
+
+impl<T: Scalar + Copy + PartialOrd + NumCast, const N: usize> FusedIterator
+    for PointGridIterator<T, N>
+{
+}
+
+// The points of a box (inclusive corners) less than `thickness` steps away from
+// its surface, in row-major order. Skips over the inside instead of looking
+// at every point.
+#[derive(Debug, Clone)]
+pub struct ShellIterator<T: Scalar, const N: usize> {
+    min: Point<T, N>,
+    max: Point<T, N>,
+    // the part that is left out, None if nothing is
+    inner: Option<(Point<T, N>, Point<T, N>)>,
+    next: Option<Point<T, N>>,
+}
+
+impl<T: Scalar + Copy + PartialOrd, const N: usize> ShellIterator<T, N> {
+    pub fn new(min: Point<T, N>, max: Point<T, N>, thickness: T) -> Self {
+        let empty = thickness <= T::zero() || (0..N).any(|i| max[i] < min[i]);
+        let inner_min = min + Point::filled(thickness);
+        let inner_max = max - Point::filled(thickness);
+        Self {
+            min,
+            max,
+            inner: (0..N)
+                .all(|i| inner_min[i] <= inner_max[i])
+                .then_some((inner_min, inner_max)),
+            next: (!empty).then_some(min),
+        }
+    }
+
+    fn advance(&self, mut p: Point<T, N>) -> Option<Point<T, N>> {
+        for i in (0..N).rev() {
+            if p[i] < self.max[i] {
+                p[i] += T::one();
+                if i == N - 1 {
+                    if let Some((inner_min, inner_max)) = self.inner {
+                        let inside =
+                            (0..N - 1).all(|j| inner_min[j] <= p[j] && p[j] <= inner_max[j]);
+                        if inside && p[i] == inner_min[i] {
+                            p[i] = inner_max[i] + T::one();
+                        }
+                    }
+                }
+                return Some(p);
+            }
+            p[i] = self.min[i];
+        }
+        None
+    }
+}
+
+impl<T: Scalar + Copy + PartialOrd, const N: usize> Iterator for ShellIterator<T, N> {
+    type Item = Point<T, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next?;
+        self.next = self.advance(result);
+        Some(result)
+    }
+}
+
+impl<T: Scalar + Copy + PartialOrd, const N: usize> FusedIterator for ShellIterator<T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::{Aabb, Point2};
+    use crate::helpers::random::Random;
+
+    // a random box, may be empty or inverted along some axes
+    fn range<const N: usize>(
+        random: &mut Random,
+    ) -> (Point<isize, N>, Point<isize, N>, Point<isize, N>) {
+        let mut below = |n| random.below(n) as isize;
+        let lower = Point(std::array::from_fn(|_| below(7) - 3));
+        let upper = Point(std::array::from_fn(|i| lower[i] + below(6) - 1));
+        let step = Point(std::array::from_fn(|_| below(3) + 1));
+        (lower, upper, step)
+    }
+
+    // Compares everything the iterators can do against the points the
+    // nested loops found, in row-major order.
+    fn check<const N: usize>(
+        lower: Point<isize, N>,
+        upper: Point<isize, N>,
+        step: Point<isize, N>,
+        expected: Vec<Point<isize, N>>,
+    ) {
+        let iter = PointGridIterator::with_step(lower, upper, step);
+        assert_eq!(iter.len(), expected.len());
+        assert_eq!(iter.clone().collect::<Vec<_>>(), expected);
+
+        let mut reversed = expected.clone();
+        reversed.reverse();
+        assert_eq!(iter.clone().rev().collect::<Vec<_>>(), reversed);
+
+        // alternating between both ends meets in the middle
+        let mut both = iter.clone();
+        let (mut front, mut back) = (vec![], vec![]);
+        while let Some(p) = both.next() {
+            front.push(p);
+            assert_eq!(both.len(), expected.len() - front.len() - back.len());
+            if let Some(q) = both.next_back() {
+                back.push(q);
+            }
+        }
+        assert_eq!(both.next(), None);
+        assert_eq!(both.next_back(), None);
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, expected);
+
+        for n in [0, 1, 3, expected.len()] {
+            assert_eq!(iter.clone().nth(n), expected.get(n).copied());
+        }
+
+        // same points, ordered by the reversed coordinates
+        let mut column_major = expected.clone();
+        column_major.sort_by_key(|p| {
+            let mut key = p.0;
+            key.reverse();
+            key
+        });
+        assert_eq!(iter.column_major().collect::<Vec<_>>(), column_major);
+
+        // the shell only makes sense without steps, upper is exclusive
+        if step == Point::filled(1) {
+            let max = upper - Point::filled(1);
+            for thickness in 0..3 {
+                let shell = expected
+                    .iter()
+                    .filter(|p| {
+                        (0..N).any(|i| p[i] - lower[i] < thickness || max[i] - p[i] < thickness)
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    ShellIterator::new(lower, max, thickness).collect::<Vec<_>>(),
+                    shell
+                );
+            }
+        }
+    }
+
+    fn steps(lower: isize, upper: isize, step: isize) -> impl Iterator<Item = isize> + Clone {
+        (lower..upper).step_by(step as usize)
+    }
+
+    #[test]
+    fn test_against_nested_loops_1d() {
+        let mut random = Random::new(1);
+        for _ in 0..100 {
+            let (l, u, s) = range::<1>(&mut random);
+            let expected = steps(l[0], u[0], s[0]).map(|a| Point([a])).collect();
+            check(l, u, s, expected);
+        }
+    }
+
+    #[test]
+    fn test_against_nested_loops_2d() {
+        let mut random = Random::new(2);
+        for _ in 0..100 {
+            let (l, u, s) = range::<2>(&mut random);
+            let mut expected = vec![];
+            for a in steps(l[0], u[0], s[0]) {
+                for b in steps(l[1], u[1], s[1]) {
+                    expected.push(Point([a, b]));
+                }
+            }
+            check(l, u, s, expected);
+        }
+    }
+
+    #[test]
+    fn test_against_nested_loops_3d() {
+        let mut random = Random::new(3);
+        for _ in 0..100 {
+            let (l, u, s) = range::<3>(&mut random);
+            let mut expected = vec![];
+            for a in steps(l[0], u[0], s[0]) {
+                for b in steps(l[1], u[1], s[1]) {
+                    for c in steps(l[2], u[2], s[2]) {
+                        expected.push(Point([a, b, c]));
+                    }
+                }
+            }
+            check(l, u, s, expected);
+        }
+    }
+
+    #[test]
+    fn test_against_nested_loops_4d() {
+        let mut random = Random::new(4);
+        for _ in 0..100 {
+            let (l, u, s) = range::<4>(&mut random);
+            let mut expected = vec![];
+            for a in steps(l[0], u[0], s[0]) {
+                for b in steps(l[1], u[1], s[1]) {
+                    for c in steps(l[2], u[2], s[2]) {
+                        for d in steps(l[3], u[3], s[3]) {
+                            expected.push(Point([a, b, c, d]));
+                        }
+                    }
+                }
+            }
+            check(l, u, s, expected);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_ranges() {
+        let p = Point2::new(2, 5);
+        assert_eq!(PointGridIterator::new(p, p).count(), 0);
+        assert_eq!(PointGridIterator::new(p, Point2::new(3, 5)).count(), 0);
+        assert_eq!(PointGridIterator::new(p, Point2::new(0, 9)).count(), 0);
+        assert_eq!(
+            PointGridIterator::new(p, Point2::new(3, 6)).collect::<Vec<_>>(),
+            vec![p]
+        );
+    }
+
+    #[test]
+    fn test_border() {
+        // ####
+        // #..#
+        // ####
+        let aabb = Aabb::new(Point2::new(0, 0), Point2::new(3, 2));
+        let border = aabb.border().collect::<Vec<_>>();
+        assert_eq!(border.len(), 10);
+        assert!(!border.contains(&Point2::new(1, 1)));
+        assert!(!border.contains(&Point2::new(2, 1)));
+        assert!(border.iter().all(|p| aabb.on_border(p)));
+        assert_eq!(aabb.shell(2).count(), 12);
+        let single = Point2::new(7, 7);
+        assert_eq!(
+            Aabb::from_point(single).border().collect::<Vec<_>>(),
+            vec![single]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Point, Point2, PointGrid};
+
+// Reading puzzle input like
+//
+//     #..
+//     .#.
+//
+// into a grid, with x going right along a line and y going down the lines.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridParseError {
+    // column and line in the text, both starting at 0
+    pub position: Point2<isize>,
+    pub character: char,
+}
+
+impl fmt::Display for GridParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown character {:?} at line {}, column {}",
+            self.character, self.position.0[1], self.position.0[0]
+        )
+    }
+}
+
+impl std::error::Error for GridParseError {}
+
+impl<const N: usize, U> PointGrid<isize, N, U> {
+    // Every character becomes a cell, parsed with the `FromStr` of the cell
+    // type (e.g. an enum deriving `parse_display::FromStr`).
+    pub fn parse(text: &str) -> Result<Self, GridParseError>
+    where
+        U: FromStr,
+    {
+        Self::parse_slice(text, Point::zero(), &[])
+    }
+
+    pub fn parse_with(text: &str, f: impl Fn(char) -> Option<U>) -> Result<Self, GridParseError> {
+        Self::parse_slice_with(text, Point::zero(), &[], f)
+    }
+
+    pub fn parse_slice(
+        text: &str,
+        slice: Point<isize, N>,
+        empty: &[char],
+    ) -> Result<Self, GridParseError>
+    where
+        U: FromStr,
+    {
+        Self::parse_slice_with(text, slice, empty, |c| c.to_string().parse().ok())
+    }
+
+    // Places the text in the plane of the first two dimensions through
+    // `slice`, i.e. the character at column x, line y ends up at
+    // `slice + (x, y, 0, ...)`. Characters in `empty` are left out of the grid.
+    pub fn parse_slice_with(
+        text: &str,
+        slice: Point<isize, N>,
+        empty: &[char],
+        f: impl Fn(char) -> Option<U>,
+    ) -> Result<Self, GridParseError> {
+        assert!(
+            N >= 2,
+            "text can only be parsed into grids with at least 2 dimensions"
+        );
+
+        let mut grid = Self::default();
+        for (y, line) in text.lines().enumerate() {
+            for (x, character) in line.chars().enumerate() {
+                if empty.contains(&character) {
+                    continue;
+                }
+                let position = Point2::new(x as isize, y as isize);
+                let cell = f(character).ok_or(GridParseError {
+                    position,
+                    character,
+                })?;
+                let mut p = slice;
+                p.0[0] += position.0[0];
+                p.0[1] += position.0[1];
+                grid.insert(p, cell);
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::Point3;
+
+    #[derive(Debug, PartialEq)]
+    enum Cell {
+        Wall,
+        Open,
+    }
+
+    impl FromStr for Cell {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "#" => Ok(Cell::Wall),
+                "." => Ok(Cell::Open),
+                _ => Err(()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = PointGrid::<isize, 2, Cell>::parse("#.\n.#\n").unwrap();
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid.get(&Point2::new(1, 0)), Some(&Cell::Open));
+        assert_eq!(grid.get(&Point2::new(1, 1)), Some(&Cell::Wall));
+
+        let digits = PointGrid::<isize, 2, u32>::parse_with("12\n34", |c| c.to_digit(10)).unwrap();
+        assert_eq!(digits.get(&Point2::new(0, 1)), Some(&3));
+    }
+
+    #[test]
+    fn test_parse_slice() {
+        let grid =
+            PointGrid::<isize, 3, Cell>::parse_slice(".#.\n..#", Point3::new(10, 0, -2), &['.'])
+                .unwrap();
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.get(&Point3::new(11, 0, -2)), Some(&Cell::Wall));
+        assert_eq!(grid.get(&Point3::new(12, 1, -2)), Some(&Cell::Wall));
+        assert_eq!(grid.get(&Point3::new(10, 0, -2)), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = PointGrid::<isize, 2, Cell>::parse("#.\n.x#").unwrap_err();
+        assert_eq!(
+            error,
+            GridParseError {
+                position: Point2::new(1, 1),
+                character: 'x'
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "unknown character 'x' at line 1, column 1"
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use std::fmt;
+use std::ops;
+
+use super::Point2;
+
+// Hexagonal grid with pointy-topped cells, so neighbours lie east and west
+// plus the four diagonals. Uses axial coordinates: q grows to the east, r to
+// the south east. The third cube coordinate is s = -q - r.
+//
+// `Point2<isize>::from(hex)` gives the axial coordinates as a point, so hex
+// cells can be stored in a `PointGrid` and `Hex::neighbour_offsets` can
+// replace the usual square neighbourhood.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::SouthEast,
+        HexDirection::SouthWest,
+        HexDirection::West,
+        HexDirection::NorthWest,
+        HexDirection::NorthEast,
+    ];
+
+    pub fn offset(&self) -> Hex {
+        match self {
+            HexDirection::East => Hex::new(1, 0),
+            HexDirection::SouthEast => Hex::new(0, 1),
+            HexDirection::SouthWest => Hex::new(-1, 1),
+            HexDirection::West => Hex::new(-1, 0),
+            HexDirection::NorthWest => Hex::new(0, -1),
+            HexDirection::NorthEast => Hex::new(1, -1),
+        }
+    }
+
+    // Parses a run of directions without separators, e.g. "esenee".
+    pub fn parse_all(s: &str) -> Result<Vec<HexDirection>, HexParseError> {
+        let mut result = vec![];
+        let mut chars = s.char_indices();
+        while let Some((i, c)) = chars.next() {
+            let direction = match c {
+                'e' => HexDirection::East,
+                'w' => HexDirection::West,
+                'n' | 's' => match (c, chars.next().map(|(_, d)| d)) {
+                    ('n', Some('e')) => HexDirection::NorthEast,
+                    ('n', Some('w')) => HexDirection::NorthWest,
+                    ('s', Some('e')) => HexDirection::SouthEast,
+                    ('s', Some('w')) => HexDirection::SouthWest,
+                    _ => return Err(HexParseError { position: i }),
+                },
+                _ => return Err(HexParseError { position: i }),
+            };
+            result.push(direction);
+        }
+        Ok(result)
+    }
+}
+
+impl fmt::Display for HexDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            HexDirection::East => "e",
+            HexDirection::SouthEast => "se",
+            HexDirection::SouthWest => "sw",
+            HexDirection::West => "w",
+            HexDirection::NorthWest => "nw",
+            HexDirection::NorthEast => "ne",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexParseError {
+    // byte offset of the direction that couldn't be read
+    pub position: usize,
+}
+
+impl fmt::Display for HexParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid hex direction at position {}", self.position)
+    }
+}
+
+impl std::error::Error for HexParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl Hex {
+    pub fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    pub fn origin() -> Self {
+        Self::default()
+    }
+
+    pub fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (isize, isize, isize) {
+        (self.q, self.r, self.s())
+    }
+
+    // The tile reached by following the directions from the origin.
+    pub fn walk(directions: &[HexDirection]) -> Self {
+        directions
+            .iter()
+            .fold(Self::origin(), |acc, d| acc.neighbour(*d))
+    }
+
+    pub fn neighbour(&self, direction: HexDirection) -> Self {
+        *self + direction.offset()
+    }
+
+    pub fn neighbours(&self) -> [Self; 6] {
+        HexDirection::ALL.map(|d| self.neighbour(d))
+    }
+
+    // the six directions as axial points, for use with `PointGrid`
+    pub fn neighbour_offsets() -> Vec<Point2<isize>> {
+        HexDirection::ALL
+            .iter()
+            .map(|d| d.offset().into())
+            .collect()
+    }
+
+    // number of steps between the two cells
+    pub fn distance(&self, other: &Self) -> isize {
+        (*self - *other).length()
+    }
+
+    pub fn length(&self) -> isize {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    // all cells with exactly the given distance, clockwise starting north west
+    pub fn ring(&self, radius: usize) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut current = *self + HexDirection::NorthWest.offset() * radius as isize;
+        let mut result = Vec::with_capacity(6 * radius);
+        for direction in HexDirection::ALL {
+            for _ in 0..radius {
+                result.push(current);
+                current = current.neighbour(direction);
+            }
+        }
+        result
+    }
+
+    // all cells up to the given distance, ring by ring from the center
+    pub fn spiral(&self, radius: usize) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+}
+
+impl From<Hex> for Point2<isize> {
+    fn from(value: Hex) -> Self {
+        Point2::new(value.q, value.r)
+    }
+}
+
+impl From<Point2<isize>> for Hex {
+    fn from(value: Point2<isize>) -> Self {
+        Hex::new(value.0[0], value.0[1])
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+impl ops::Add for Hex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl ops::Sub for Hex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl ops::Mul<isize> for Hex {
+    type Output = Self;
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl ops::AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::PointGrid;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_parse_and_walk() {
+        let directions = HexDirection::parse_all("esenee").unwrap();
+        assert_eq!(
+            directions,
+            vec![
+                HexDirection::East,
+                HexDirection::SouthEast,
+                HexDirection::NorthEast,
+                HexDirection::East
+            ]
+        );
+        assert_eq!(Hex::walk(&directions), Hex::new(3, 0));
+        let back = HexDirection::parse_all("nwwswee").unwrap();
+        assert_eq!(Hex::walk(&back), Hex::origin());
+        assert_eq!(
+            directions.iter().map(|d| d.to_string()).collect::<String>(),
+            "esenee"
+        );
+        assert_eq!(
+            HexDirection::parse_all("esx"),
+            Err(HexParseError { position: 1 })
+        );
+        assert_eq!(
+            HexDirection::parse_all("en"),
+            Err(HexParseError { position: 1 })
+        );
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Hex::new(2, -3);
+        assert_eq!(a.cube(), (2, -3, 1));
+        assert_eq!(a.length(), 3);
+        assert_eq!(a.distance(&Hex::new(-1, 2)), 5);
+        for n in Hex::origin().neighbours() {
+            assert_eq!(n.length(), 1);
+        }
+    }
+
+    #[test]
+    fn test_ring_and_spiral() {
+        let center = Hex::new(4, -2);
+        assert_eq!(center.ring(0), vec![center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius);
+            assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+            assert!(ring.iter().all(|h| h.distance(&center) == radius as isize));
+        }
+        // centered hexagonal numbers
+        let spiral = center.spiral(3);
+        assert_eq!(spiral.len(), 37);
+        assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), 37);
+    }
+
+    #[test]
+    fn test_point_grid_keys() {
+        let mut grid = PointGrid::default();
+        grid.insert(Hex::new(1, -1).into(), true);
+        let origin: Point2<isize> = Hex::origin().into();
+        let neighbours = Hex::neighbour_offsets()
+            .iter()
+            .filter(|d| grid.get(&(origin + **d)).is_some())
+            .count();
+        assert_eq!(neighbours, 1);
+        assert_eq!(Hex::from(Point2::new(1, -1)), Hex::new(1, -1));
+    }
+}
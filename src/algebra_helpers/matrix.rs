@@ -0,0 +1,428 @@
+use std::ops::{self, Index, IndexMut};
+use std::{array, fmt};
+
+use super::{Field, Point, Scalar};
+
+// Dense R x C matrix stored row by row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Matrix<T: Scalar, const R: usize, const C: usize>(pub [[T; C]; R]);
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn zero() -> Self {
+        Self([[T::zero(); C]; R])
+    }
+
+    pub fn from_fn(f: impl Fn(usize, usize) -> T) -> Self {
+        Self(array::from_fn(|r| array::from_fn(|c| f(r, c))))
+    }
+
+    pub fn from_rows(rows: [Point<T, C>; R]) -> Self {
+        Self(rows.map(|p| p.0))
+    }
+
+    pub fn row(&self, r: usize) -> Point<T, C> {
+        Point(self.0[r])
+    }
+
+    pub fn column(&self, c: usize) -> Point<T, R> {
+        Point(array::from_fn(|r| self.0[r][c]))
+    }
+
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        Matrix::from_fn(|r, c| self.0[c][r])
+    }
+
+    // Fraction-free Gaussian elimination (Bareiss algorithm). Returns the row
+    // echelon form, its rank and whether an odd number of row swaps happened.
+    // All divisions are by an earlier pivot and exact, so this works for
+    // integers without leaving the ring, as well as for rationals, floats and
+    // ModInt with a prime modulus. For a composite modulus a pivot can be a
+    // zero divisor, use `field_echelon_form` which picks invertible pivots.
+    pub fn echelon_form(&self) -> (Self, usize, bool) {
+        let mut m = self.0;
+        let mut previous_pivot = T::one();
+        let mut rank = 0;
+        let mut swapped = false;
+
+        for col in 0..C {
+            if rank == R {
+                break;
+            }
+            let Some(pivot_row) = (rank..R).find(|&r| !m[r][col].is_zero()) else {
+                continue;
+            };
+            if pivot_row != rank {
+                m.swap(pivot_row, rank);
+                swapped = !swapped;
+            }
+
+            let pivot = m[rank][col];
+            for r in (rank + 1)..R {
+                for c in (col + 1)..C {
+                    m[r][c] = (m[r][c] * pivot - m[r][col] * m[rank][c]) / previous_pivot;
+                }
+                m[r][col] = T::zero();
+            }
+            previous_pivot = pivot;
+            rank += 1;
+        }
+
+        (Self(m), rank, swapped)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.echelon_form().1
+    }
+}
+
+impl<T: Field, const R: usize, const C: usize> Matrix<T, R, C> {
+    // Plain Gaussian elimination for fields, dividing by each pivot. Same
+    // result as `echelon_form` up to scaling of the rows. Panics if a column
+    // has non-zero entries but none of them is invertible, which only
+    // happens for ModInt with a composite modulus.
+    pub fn field_echelon_form(&self) -> (Self, usize, bool) {
+        let mut m = self.0;
+        let (rank, swapped) = eliminate(&mut m, &mut [T::zero(); R]);
+        (Self(m), rank, swapped)
+    }
+}
+
+// Reduces m to row echelon form in place, applying the same row operations
+// to rhs. Returns the rank and whether an odd number of row swaps happened.
+fn eliminate<T: Field, const R: usize, const C: usize>(
+    m: &mut [[T; C]; R],
+    rhs: &mut [T; R],
+) -> (usize, bool) {
+    let mut rank = 0;
+    let mut swapped = false;
+
+    for col in 0..C {
+        if rank == R {
+            break;
+        }
+        let Some(pivot_row) = (rank..R).find(|&r| m[r][col].inverse().is_some()) else {
+            assert!(
+                (rank..R).all(|r| m[r][col].is_zero()),
+                "no invertible pivot in column {}, the scalar is not a field",
+                col
+            );
+            continue;
+        };
+        if pivot_row != rank {
+            m.swap(pivot_row, rank);
+            rhs.swap(pivot_row, rank);
+            swapped = !swapped;
+        }
+
+        let pivot = m[rank];
+        let inverse = pivot[col].inverse().unwrap();
+        for r in (rank + 1)..R {
+            let factor = m[r][col] * inverse;
+            for (e, p) in m[r][(col + 1)..].iter_mut().zip(&pivot[(col + 1)..]) {
+                *e -= factor * *p;
+            }
+            m[r][col] = T::zero();
+            let pivot_rhs = rhs[rank];
+            rhs[r] -= factor * pivot_rhs;
+        }
+        rank += 1;
+    }
+
+    (rank, swapped)
+}
+
+impl<T: Scalar + Copy, const N: usize> Matrix<T, N, N> {
+    pub fn identity() -> Self {
+        Self::from_fn(|r, c| if r == c { T::one() } else { T::zero() })
+    }
+
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut base = *self;
+        let mut result = Self::identity();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base * base;
+            }
+        }
+        result
+    }
+
+    // For the recurrence a_n = c_0 * a_{n-1} + ... + c_{N-1} * a_{n-N}.
+    // Applied to (a_{n-1}, ..., a_{n-N}) it yields (a_n, ..., a_{n-N+1}).
+    pub fn companion(coefficients: [T; N]) -> Self {
+        Self::from_fn(|r, c| {
+            if r == 0 {
+                coefficients[c]
+            } else if r == c + 1 {
+                T::one()
+            } else {
+                T::zero()
+            }
+        })
+    }
+
+    // With Bareiss elimination the last pivot of a full rank square matrix is
+    // its determinant. Needs a signed scalar if rows have to be swapped.
+    pub fn determinant(&self) -> T {
+        if N == 0 {
+            return T::one();
+        }
+        let (m, rank, swapped) = self.echelon_form();
+        if rank < N {
+            return T::zero();
+        }
+        if swapped {
+            T::zero() - m.0[N - 1][N - 1]
+        } else {
+            m.0[N - 1][N - 1]
+        }
+    }
+
+    // Solves self * x = b with Cramer's rule. Returns the numerators of x
+    // together with their common denominator det(self), so that integer
+    // systems can be solved without leaving the integers. None if singular.
+    pub fn solve(&self, b: Point<T, N>) -> Option<(Point<T, N>, T)> {
+        let det = self.determinant();
+        if det.is_zero() {
+            return None;
+        }
+        let numerators = array::from_fn(|i| {
+            Self::from_fn(|r, c| if c == i { b[r] } else { self.0[r][c] }).determinant()
+        });
+        Some((Point(numerators), det))
+    }
+}
+
+impl<T: Field, const N: usize> Matrix<T, N, N> {
+    // The product of the pivots of `field_echelon_form`.
+    pub fn field_determinant(&self) -> T {
+        let (m, rank, swapped) = self.field_echelon_form();
+        if rank < N {
+            return T::zero();
+        }
+        let product = (0..N).fold(T::one(), |acc, i| acc * m.0[i][i]);
+        if swapped {
+            T::zero() - product
+        } else {
+            product
+        }
+    }
+
+    // Solves self * x = b by elimination and back substitution. None if
+    // singular.
+    pub fn field_solve(&self, b: Point<T, N>) -> Option<Point<T, N>> {
+        let mut m = self.0;
+        let mut rhs = b.0;
+        if eliminate(&mut m, &mut rhs).0 < N {
+            return None;
+        }
+        let mut x = [T::zero(); N];
+        for r in (0..N).rev() {
+            let known = ((r + 1)..N).fold(T::zero(), |acc, c| acc + m[r][c] * x[c]);
+            x[r] = (rhs[r] - known) * m[r][r].inverse().unwrap();
+        }
+        Some(Point(x))
+    }
+}
+
+// Nth term of a linear recurrence in O(N^3 log n), see `Matrix::companion`.
+// `initial` holds a_0, ..., a_{N-1}.
+pub fn linear_recurrence<T: Scalar + Copy, const N: usize>(
+    coefficients: [T; N],
+    initial: [T; N],
+    n: u64,
+) -> T {
+    if (n as usize) < N {
+        return initial[n as usize];
+    }
+    let mut state = initial;
+    state.reverse();
+    let m = Matrix::companion(coefficients).pow(n - (N as u64 - 1));
+    (m * Point(state))[0]
+}
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        &self.0[r][c]
+    }
+}
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> IndexMut<(usize, usize)>
+    for Matrix<T, R, C>
+{
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Self::Output {
+        &mut self.0[r][c]
+    }
+}
+
+impl<T: Scalar + Copy + fmt::Display, const R: usize, const C: usize> fmt::Display
+    for Matrix<T, R, C>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.0.iter() {
+            for (i, e) in row.iter().enumerate() {
+                if i != 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", e)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> ops::Add for Matrix<T, R, C> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_fn(|r, c| self.0[r][c] + rhs.0[r][c])
+    }
+}
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> ops::Sub for Matrix<T, R, C> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from_fn(|r, c| self.0[r][c] - rhs.0[r][c])
+    }
+}
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> ops::Mul<T> for Matrix<T, R, C> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::from_fn(|r, c| self.0[r][c] * rhs)
+    }
+}
+
+impl<T: Scalar + Copy, const R: usize, const K: usize, const C: usize> ops::Mul<Matrix<T, K, C>>
+    for Matrix<T, R, K>
+{
+    type Output = Matrix<T, R, C>;
+    fn mul(self, rhs: Matrix<T, K, C>) -> Self::Output {
+        Matrix::from_fn(|r, c| (0..K).fold(T::zero(), |acc, k| acc + self.0[r][k] * rhs.0[k][c]))
+    }
+}
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> ops::Mul<Point<T, C>> for Matrix<T, R, C> {
+    type Output = Point<T, R>;
+    fn mul(self, rhs: Point<T, C>) -> Self::Output {
+        Point(array::from_fn(|r| {
+            (0..C).fold(T::zero(), |acc, c| acc + self.0[r][c] * rhs[c])
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::Point2;
+    use crate::helpers::modular::ModInt;
+    use crate::helpers::rational::Rational;
+    use num_traits::{One, Zero};
+
+    #[test]
+    fn test_multiplication() {
+        let a = Matrix([[1, 2, 3], [4, 5, 6]]);
+        let b = Matrix([[7, 8], [9, 10], [11, 12]]);
+        assert_eq!(a * b, Matrix([[58, 64], [139, 154]]));
+        assert_eq!(a.transpose(), Matrix([[1, 4], [2, 5], [3, 6]]));
+        // rotation by 90 degrees
+        let rot = Matrix([[0, -1], [1, 0]]);
+        assert_eq!(rot * Point2::new(3, 1), Point2::new(-1, 3));
+        assert_eq!(rot.pow(4), Matrix::identity());
+    }
+
+    #[test]
+    fn test_linear_recurrence() {
+        // fibonacci
+        assert_eq!(
+            linear_recurrence([1i64, 1], [0, 1], 90),
+            2880067194370816120
+        );
+        type P = ModInt<1_000_000_007>;
+        let fib_mod = linear_recurrence([P::one(); 2], [P::zero(), P::one()], 90);
+        assert_eq!(fib_mod.value(), 2880067194370816120 % 1_000_000_007);
+        // arrangements of a run of adapters with joltage gaps of one (day 10)
+        // follow the tribonacci numbers
+        let arrangements = |run: u64| linear_recurrence([1u64, 1, 1], [1, 1, 2], run);
+        assert_eq!(
+            (0..7).map(arrangements).collect::<Vec<_>>(),
+            vec![1, 1, 2, 4, 7, 13, 24]
+        );
+    }
+
+    #[test]
+    fn test_determinant_and_rank() {
+        let m = Matrix([[2i64, -3, 1], [2, 0, -1], [1, 4, 5]]);
+        assert_eq!(m.determinant(), 49);
+        assert_eq!(m.rank(), 3);
+        let swapped = Matrix([[0i64, 1], [1, 0]]);
+        assert_eq!(swapped.determinant(), -1);
+        let singular = Matrix([[1i64, 2, 3], [2, 4, 6], [1, 0, 1]]);
+        assert_eq!(singular.determinant(), 0);
+        assert_eq!(singular.rank(), 2);
+        assert_eq!(Matrix([[0i64, 0, 1], [0, 0, 2]]).rank(), 1);
+
+        let r = |n, d| Rational::new(n, d);
+        let rational = Matrix([[r(1, 2), r(1, 3)], [r(1, 4), r(1, 5)]]);
+        assert_eq!(rational.determinant(), r(1, 60));
+
+        let modular = Matrix([[2, 3], [1, 4]].map(|row| row.map(ModInt::<7>::new)));
+        assert_eq!(modular.determinant(), ModInt::new(5));
+    }
+
+    #[test]
+    fn test_solve() {
+        // 2x + y = 5, x - 3y = -1 => x = 2, y = 1
+        let m = Matrix([[2i64, 1], [1, -3]]);
+        let (x, d) = m.solve(Point2::new(5, -1)).unwrap();
+        assert_eq!((x[0] / d, x[1] / d), (2, 1));
+        // x + y = 1, x - y = 0 has the non integer solution (1/2, 1/2)
+        let (x, d) = Matrix([[1i64, 1], [1, -1]])
+            .solve(Point2::new(1, 0))
+            .unwrap();
+        assert_eq!(
+            (Rational::new(x[0], d), Rational::new(x[1], d)),
+            (Rational::new(1, 2), Rational::new(1, 2))
+        );
+        assert_eq!(Matrix([[1i64, 2], [2, 4]]).solve(Point2::new(1, 2)), None);
+    }
+
+    #[test]
+    fn test_field_elimination() {
+        let m = Matrix([[2, -3, 1], [2, 0, -1], [1, 4, 5]].map(|row| row.map(ModInt::<11>::new)));
+        assert_eq!(m.field_determinant(), ModInt::new(49));
+        assert_eq!(m.field_determinant(), m.determinant());
+        assert_eq!(m.field_echelon_form().1, 3);
+
+        let r = |n, d| Rational::new(n, d);
+        let rational = Matrix([[r(1, 2), r(1, 3)], [r(1, 4), r(1, 5)]]);
+        assert_eq!(rational.field_determinant(), r(1, 60));
+        let x = Matrix([[r(1, 1), r(1, 1)], [r(1, 1), r(-1, 1)]])
+            .field_solve(Point2::new(r(1, 1), r(0, 1)));
+        assert_eq!(x, Some(Point2::new(r(1, 2), r(1, 2))));
+        assert_eq!(
+            Matrix([[1.0, 2.0], [2.0, 4.0]]).field_solve(Point2::new(1.0, 2.0)),
+            None
+        );
+
+        // modulo 6 the first pivot 2 is a zero divisor, Bareiss would divide
+        // by it, the field elimination swaps in the invertible 1 instead
+        type M6 = ModInt<6>;
+        let m = Matrix([[2, 1], [1, 1]].map(|row| row.map(M6::new)));
+        assert_eq!(m.field_determinant(), M6::one());
+        let x = m.field_solve(Point2::new(M6::new(3), M6::new(2))).unwrap();
+        assert_eq!(m * x, Point2::new(M6::new(3), M6::new(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "no invertible pivot")]
+    fn test_field_elimination_composite_modulus() {
+        let m = Matrix([[2, 1], [3, 2]].map(|row| row.map(ModInt::<6>::new)));
+        m.field_determinant();
+    }
+}
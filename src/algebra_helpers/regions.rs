@@ -0,0 +1,267 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::{Aabb, Point, PointGrid};
+
+// Regions of a grid: flood fill, connected components and enclosed holes.
+
+// Which cells touch. In 2D orthogonal is the 4-neighbourhood and full the
+// 8-neighbourhood, in 3D 6 and 26 and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Orthogonal,
+    Full,
+}
+
+impl Connectivity {
+    pub fn offsets<const N: usize>(&self) -> Vec<Point<isize, N>> {
+        match self {
+            Connectivity::Orthogonal => Point::directions(),
+            Connectivity::Full => Point::directions_with_diagonals(),
+        }
+    }
+}
+
+// one connected component, points sorted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<const N: usize> {
+    pub points: Vec<Point<isize, N>>,
+    pub bounds: Aabb<isize, N>,
+}
+
+impl<const N: usize> Region<N> {
+    fn new(mut points: Vec<Point<isize, N>>) -> Self {
+        points.sort_by_key(|p| p.0);
+        let bounds = Aabb::from_points(points.iter().copied()).unwrap();
+        Self { points, bounds }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn contains(&self, p: &Point<isize, N>) -> bool {
+        self.points.binary_search_by_key(&p.0, |q| q.0).is_ok()
+    }
+}
+
+// Breadth first from start over the points `inside` accepts, adding them to
+// `seen`. Returns the points reached, start included.
+fn fill<const N: usize>(
+    start: Point<isize, N>,
+    offsets: &[Point<isize, N>],
+    inside: impl Fn(&Point<isize, N>) -> bool,
+    seen: &mut HashSet<Point<isize, N>>,
+) -> Vec<Point<isize, N>> {
+    let mut result = vec![];
+    let mut queue = VecDeque::from([start]);
+    seen.insert(start);
+    while let Some(p) = queue.pop_front() {
+        result.push(p);
+        for d in offsets {
+            let q = p + *d;
+            if !seen.contains(&q) && inside(&q) {
+                seen.insert(q);
+                queue.push_back(q);
+            }
+        }
+    }
+    result
+}
+
+impl<const N: usize, U> PointGrid<isize, N, U> {
+    fn matches(&self, p: &Point<isize, N>, predicate: &impl Fn(&U) -> bool) -> bool {
+        self.get(p).is_some_and(predicate)
+    }
+
+    // All cells connected to seed through cells the predicate accepts, empty
+    // if the seed itself isn't accepted.
+    pub fn flood_fill(
+        &self,
+        seed: Point<isize, N>,
+        connectivity: Connectivity,
+        predicate: impl Fn(&U) -> bool,
+    ) -> HashSet<Point<isize, N>> {
+        let mut seen = HashSet::new();
+        if self.matches(&seed, &predicate) {
+            let offsets = connectivity.offsets();
+            fill(seed, &offsets, |p| self.matches(p, &predicate), &mut seen);
+        }
+        seen
+    }
+
+    // The connected regions of accepted cells, ordered by their first point.
+    pub fn components(
+        &self,
+        connectivity: Connectivity,
+        predicate: impl Fn(&U) -> bool,
+    ) -> Vec<Region<N>> {
+        let offsets = connectivity.offsets();
+        let mut seeds = self
+            .iter()
+            .filter(|(_, u)| predicate(u))
+            .map(|(p, _)| *p)
+            .collect::<Vec<_>>();
+        seeds.sort_by_key(|p| p.0);
+
+        let mut seen = HashSet::new();
+        let mut result = vec![];
+        for seed in seeds {
+            if !seen.contains(&seed) {
+                let points = fill(seed, &offsets, |p| self.matches(p, &predicate), &mut seen);
+                result.push(Region::new(points));
+            }
+        }
+        result
+    }
+
+    // The open regions inside the bounding box of the grid that can't reach
+    // its outside. Every cell that isn't a wall is open, including points
+    // missing from the grid. `connectivity` is how the open space connects,
+    // usually the opposite of the walls: 4-connected walls only enclose
+    // anything if the space can't slip through diagonally, so use Full.
+    pub fn holes(
+        &self,
+        connectivity: Connectivity,
+        is_wall: impl Fn(&U) -> bool,
+    ) -> Vec<Region<N>> {
+        let Some(bounds) = self.dimensions() else {
+            return vec![];
+        };
+        let offsets = connectivity.offsets();
+        let outside_box = bounds.expand(1);
+        let open = |p: &Point<isize, N>| outside_box.contains(p) && !self.matches(p, &is_wall);
+
+        // one ring around the box is open, so the outside is connected
+        let mut seen = HashSet::new();
+        fill(outside_box.min, &offsets, open, &mut seen);
+
+        let mut result = vec![];
+        for p in bounds.iter() {
+            if !seen.contains(&p) && open(&p) {
+                result.push(Region::new(fill(p, &offsets, open, &mut seen)));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::{Point2, Point3};
+
+    fn grid(s: &str) -> PointGrid<isize, 2, char> {
+        PointGrid::parse_with(s, Some).unwrap()
+    }
+
+    fn is_land(c: &char) -> bool {
+        *c == '#'
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let map = grid("##..\n#..#\n..##\n#...");
+        let island = map.flood_fill(Point2::new(0, 0), Connectivity::Orthogonal, is_land);
+        assert_eq!(island.len(), 3);
+        let diagonal = map.flood_fill(Point2::new(0, 0), Connectivity::Full, is_land);
+        assert_eq!(diagonal.len(), 3);
+        let joined = map.flood_fill(Point2::new(3, 1), Connectivity::Full, is_land);
+        assert_eq!(joined.len(), 3);
+        assert!(map
+            .flood_fill(Point2::new(2, 0), Connectivity::Orthogonal, is_land)
+            .is_empty());
+        let sea = map.flood_fill(Point2::new(2, 0), Connectivity::Orthogonal, |c| *c == '.');
+        assert_eq!(sea.len(), 9);
+    }
+
+    #[test]
+    fn test_components() {
+        let map = grid("#..#\n.#..\n...#\n##.#");
+        let orthogonal = map.components(Connectivity::Orthogonal, is_land);
+        let sizes = orthogonal.iter().map(Region::len).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![1, 2, 1, 1, 2]);
+        assert_eq!(
+            orthogonal[4].bounds,
+            Aabb::new(Point2::new(3, 2), Point2::new(3, 3))
+        );
+        assert!(orthogonal[1].contains(&Point2::new(1, 3)));
+
+        // (0, 0) and (1, 1) only touch at a corner
+        let full = map.components(Connectivity::Full, is_land);
+        let sizes = full.iter().map(Region::len).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![2, 2, 1, 2]);
+        assert_eq!(
+            full[0].bounds,
+            Aabb::new(Point2::new(0, 0), Point2::new(1, 1))
+        );
+    }
+
+    #[test]
+    fn test_components_3d() {
+        // two cubes of 8 touching only at an edge
+        let mut space = PointGrid::default();
+        for p in Aabb::new(Point3::new(0, 0, 0), Point3::new(1, 1, 1)) {
+            space.insert(p, true);
+            space.insert(p + Point3::new(2, 2, 0), true);
+        }
+        assert_eq!(space.components(Connectivity::Orthogonal, |b| *b).len(), 2);
+        let full = space.components(Connectivity::Full, |b| *b);
+        assert_eq!(full.len(), 1);
+        assert_eq!(full[0].len(), 16);
+        assert_eq!(full[0].bounds.volume(), 32);
+    }
+
+    #[test]
+    fn test_holes() {
+        let map = grid(
+            "\
+#####..
+#..##.#
+#####.#
+.#.#...
+#.#.#..",
+        );
+        let holes = map.holes(Connectivity::Orthogonal, is_land);
+        assert_eq!(holes.len(), 2);
+        assert_eq!(holes[0].points, vec![Point2::new(1, 1), Point2::new(2, 1)]);
+
+        // diagonally the cell at the bottom leaks out, the room doesn't
+        let holes = map.holes(Connectivity::Full, is_land);
+        assert_eq!(holes.len(), 1);
+        assert_eq!(holes[0].len(), 2);
+
+        // cells missing from the grid count as open
+        let mut ring = PointGrid::default();
+        for p in Aabb::new(Point2::new(0, 0), Point2::new(4, 4)).border() {
+            ring.insert(p, '#');
+        }
+        let holes = ring.holes(Connectivity::Full, is_land);
+        assert_eq!(holes.len(), 1);
+        assert_eq!(
+            holes[0].bounds,
+            Aabb::new(Point2::new(1, 1), Point2::new(3, 3))
+        );
+        assert!(PointGrid::<isize, 2, char>::default()
+            .holes(Connectivity::Full, is_land)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_holes_3d() {
+        let mut shell = PointGrid::default();
+        for p in Aabb::new(Point3::new(0, 0, 0), Point3::new(3, 3, 3)).border() {
+            shell.insert(p, true);
+        }
+        let holes = shell.holes(Connectivity::Orthogonal, |b| *b);
+        assert_eq!(holes.len(), 1);
+        assert_eq!(holes[0].len(), 8);
+
+        // a hole in one face lets the inside out
+        shell.insert(Point3::new(1, 1, 0), false);
+        assert!(shell.holes(Connectivity::Orthogonal, |b| *b).is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+use itertools::Itertools;
+use std::array;
+use std::hash::Hash;
+use std::ops::Neg;
+
+use super::{Aabb, Point, PointGrid, Scalar};
+
+// Symmetries of the N-dimensional grid around the origin: every axis is sent
+// to some (possibly negated) other axis. In 2D these are the 8 elements of the
+// dihedral group D4, in 3D the 48 symmetries of the cube, of which 24 are
+// proper rotations (`is_rotation`).
+//
+// Applying the symmetry to p gives the point with component i equal to
+// p[permutation[i]], negated if negate[i] is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symmetry<const N: usize> {
+    permutation: [usize; N],
+    negate: [bool; N],
+}
+
+impl<const N: usize> Symmetry<N> {
+    pub fn identity() -> Self {
+        Self {
+            permutation: array::from_fn(|i| i),
+            negate: [false; N],
+        }
+    }
+
+    // all 2^N * N! symmetries, starting with the identity
+    pub fn all() -> Vec<Self> {
+        (0..N)
+            .permutations(N)
+            .cartesian_product(0..(1 << N))
+            .map(|(permutation, signs)| Self {
+                permutation: permutation.try_into().unwrap(),
+                negate: array::from_fn(|i| signs & (1 << i) != 0),
+            })
+            .collect()
+    }
+
+    // the symmetries that don't mirror, i.e. with determinant 1
+    pub fn rotations() -> Vec<Self> {
+        Self::all().into_iter().filter(Self::is_rotation).collect()
+    }
+
+    // rotates by 90 degrees in the plane of the two axes, `from` is moved
+    // onto `to`
+    pub fn quarter_turn(from: usize, to: usize) -> Self {
+        assert_ne!(from, to);
+        let mut result = Self::identity();
+        result.permutation.swap(from, to);
+        result.negate[from] = true;
+        result
+    }
+
+    // mirrors along the given axis
+    pub fn reflection(axis: usize) -> Self {
+        let mut result = Self::identity();
+        result.negate[axis] = true;
+        result
+    }
+
+    pub fn swap_axes(a: usize, b: usize) -> Self {
+        let mut result = Self::identity();
+        result.permutation.swap(a, b);
+        result
+    }
+
+    pub fn determinant(&self) -> i8 {
+        let inversions = (0..N)
+            .tuple_combinations()
+            .filter(|&(i, j)| self.permutation[i] > self.permutation[j])
+            .count();
+        let negations = self.negate.iter().filter(|n| **n).count();
+        if (inversions + negations) % 2 == 0 {
+            1
+        } else {
+            -1
+        }
+    }
+
+    pub fn is_rotation(&self) -> bool {
+        self.determinant() == 1
+    }
+
+    // the symmetry applying `first` and then `self`
+    pub fn compose(&self, first: &Self) -> Self {
+        Self {
+            permutation: array::from_fn(|i| first.permutation[self.permutation[i]]),
+            negate: array::from_fn(|i| self.negate[i] != first.negate[self.permutation[i]]),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut result = Self::identity();
+        for i in 0..N {
+            result.permutation[self.permutation[i]] = i;
+            result.negate[self.permutation[i]] = self.negate[i];
+        }
+        result
+    }
+
+    pub fn apply<T: Scalar + Copy + Neg<Output = T>>(&self, p: Point<T, N>) -> Point<T, N> {
+        Point(array::from_fn(|i| {
+            let c = p[self.permutation[i]];
+            if self.negate[i] {
+                -c
+            } else {
+                c
+            }
+        }))
+    }
+
+    // Transforms every point of the grid. The result is moved so that its
+    // bounding box starts at the same corner as before, which is what is
+    // needed for rotating and flipping tiles in place.
+    pub fn apply_to_grid<T, U>(&self, grid: PointGrid<T, N, U>) -> PointGrid<T, N, U>
+    where
+        T: Scalar + Copy + Neg<Output = T> + Ord + Hash,
+    {
+        let Some(Aabb { min, max }) = grid.dimensions() else {
+            return grid;
+        };
+        let offset = min - self.apply(min).min(self.apply(max));
+        grid.into_iter()
+            .map(|(p, u)| (self.apply(p) + offset, u))
+            .collect()
+    }
+}
+
+impl Symmetry<2> {
+    // Clockwise as seen in the puzzle text, where y points down. None if the
+    // angle isn't a multiple of 90 degrees.
+    pub fn rotation_clockwise(degrees: isize) -> Option<Self> {
+        if degrees % 90 != 0 {
+            return None;
+        }
+        let turn = Self::quarter_turn(0, 1);
+        Some((0..degrees.rem_euclid(360) / 90).fold(Self::identity(), |acc, _| turn.compose(&acc)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::{Point2, Point3};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_dihedral_group() {
+        let all = Symmetry::<2>::all();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0], Symmetry::identity());
+        assert_eq!(Symmetry::<2>::rotations().len(), 4);
+
+        // closed under composition, every element has an inverse
+        for a in &all {
+            assert_eq!(a.compose(&a.inverse()), Symmetry::identity());
+            assert_eq!(a.inverse().compose(a), Symmetry::identity());
+            for b in &all {
+                let c = a.compose(b);
+                assert!(all.contains(&c));
+                let p = Point2::new(3, -7);
+                assert_eq!(c.apply(p), a.apply(b.apply(p)));
+                assert_eq!(c.determinant(), a.determinant() * b.determinant());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotation_clockwise() {
+        // east 10, north 1 turns into south 10, east 1
+        let waypoint = Point2::new(10, -1);
+        let r = |d| Symmetry::rotation_clockwise(d).unwrap().apply(waypoint);
+        assert_eq!(r(90), Point2::new(1, 10));
+        assert_eq!(r(180), Point2::new(-10, 1));
+        assert_eq!(r(270), Point2::new(-1, -10));
+        assert_eq!(r(-90), r(270));
+        assert_eq!(r(720), waypoint);
+        assert_eq!(Symmetry::rotation_clockwise(45), None);
+        assert!(!Symmetry::<2>::reflection(0).is_rotation());
+    }
+
+    #[test]
+    fn test_cube_rotations() {
+        assert_eq!(Symmetry::<3>::all().len(), 48);
+        let rotations = Symmetry::<3>::rotations();
+        assert_eq!(rotations.len(), 24);
+        let images = rotations
+            .iter()
+            .map(|r| r.apply(Point3::new(1, 2, 3)))
+            .collect::<HashSet<_>>();
+        assert_eq!(images.len(), 24);
+
+        let about_z = Symmetry::<3>::quarter_turn(0, 1);
+        assert_eq!(about_z.apply(Point3::new(1, 0, 5)), Point3::new(0, 1, 5));
+        assert_eq!(
+            about_z
+                .compose(&about_z)
+                .compose(&about_z)
+                .compose(&about_z),
+            Symmetry::identity()
+        );
+        for r in &rotations {
+            assert!(rotations.contains(&r.inverse()));
+            assert!(rotations.contains(&r.compose(&about_z)));
+        }
+    }
+
+    #[test]
+    fn test_apply_to_grid() {
+        // ab      ca
+        // cd  ->  db
+        let mut grid = PointGrid::default();
+        grid.insert(Point2::new(5, 1), 'a');
+        grid.insert(Point2::new(6, 1), 'b');
+        grid.insert(Point2::new(5, 2), 'c');
+        grid.insert(Point2::new(6, 2), 'd');
+        let rotated = Symmetry::rotation_clockwise(90)
+            .unwrap()
+            .apply_to_grid(grid.clone());
+        assert_eq!(rotated.dimensions(), grid.dimensions());
+        assert_eq!(rotated.get(&Point2::new(5, 1)), Some(&'c'));
+        assert_eq!(rotated.get(&Point2::new(6, 1)), Some(&'a'));
+        assert_eq!(rotated.get(&Point2::new(6, 2)), Some(&'b'));
+
+        let flipped = Symmetry::reflection(0).apply_to_grid(grid);
+        assert_eq!(flipped.get(&Point2::new(5, 1)), Some(&'b'));
+        assert_eq!(flipped.get(&Point2::new(5, 2)), Some(&'d'));
+    }
+}
@@ -0,0 +1,250 @@
+use std::array;
+
+use super::{Point, PointGrid};
+
+// How positions connect at the edges of a grid. Lookups and steps go through
+// the topology, so walking off one side can end up on the other one
+// (cylinder, torus), nowhere (bounded) or anywhere else (custom mapping,
+// e.g. the faces of a cube net).
+
+// Gets the last position inside the box and the step that left it, returns
+// the position and direction after crossing the edge, or None if there is
+// nothing on the other side.
+pub type EdgeMapping<const N: usize> = Box<
+    dyn Fn(Point<isize, N>, Point<isize, N>) -> Option<(Point<isize, N>, Point<isize, N>)>
+        + Send
+        + Sync,
+>;
+
+pub enum Topology<const N: usize> {
+    Unbounded,
+    // only points inside the box (inclusive) exist
+    Bounded {
+        min: Point<isize, N>,
+        max: Point<isize, N>,
+    },
+    // Leaving the box along an axis marked in `wrap` re-enters it on the
+    // opposite side. Nothing exists if the box is empty, `cylinder` and
+    // `torus` refuse to build one.
+    Wrapping {
+        min: Point<isize, N>,
+        max: Point<isize, N>,
+        wrap: [bool; N],
+    },
+    Custom {
+        min: Point<isize, N>,
+        max: Point<isize, N>,
+        edge: EdgeMapping<N>,
+    },
+}
+
+impl<const N: usize> Topology<N> {
+    // Wraps around along one axis only. None if max < min on any axis, there
+    // would be nothing to wrap around to.
+    pub fn cylinder(min: Point<isize, N>, max: Point<isize, N>, axis: usize) -> Option<Self> {
+        Self::wrapping(min, max, array::from_fn(|i| i == axis))
+    }
+
+    // wraps around along every axis, None for an empty box like `cylinder`
+    pub fn torus(min: Point<isize, N>, max: Point<isize, N>) -> Option<Self> {
+        Self::wrapping(min, max, [true; N])
+    }
+
+    fn wrapping(min: Point<isize, N>, max: Point<isize, N>, wrap: [bool; N]) -> Option<Self> {
+        (0..N)
+            .all(|i| min[i] <= max[i])
+            .then_some(Self::Wrapping { min, max, wrap })
+    }
+
+    pub fn custom(min: Point<isize, N>, max: Point<isize, N>, edge: EdgeMapping<N>) -> Self {
+        Self::Custom { min, max, edge }
+    }
+
+    fn in_box(p: &Point<isize, N>, min: &Point<isize, N>, max: &Point<isize, N>) -> bool {
+        (0..N).all(|i| min[i] <= p[i] && p[i] <= max[i])
+    }
+
+    pub fn contains(&self, p: &Point<isize, N>) -> bool {
+        self.normalize(*p) == Some(*p)
+    }
+
+    // The position p refers to, None if it isn't part of the grid.
+    pub fn normalize(&self, p: Point<isize, N>) -> Option<Point<isize, N>> {
+        match self {
+            Self::Unbounded => Some(p),
+            Self::Bounded { min, max } | Self::Custom { min, max, .. } => {
+                Self::in_box(&p, min, max).then_some(p)
+            }
+            Self::Wrapping { min, max, wrap } => {
+                let mut result = p;
+                for i in 0..N {
+                    if wrap[i] {
+                        let size = max[i] - min[i] + 1;
+                        if size <= 0 {
+                            return None;
+                        }
+                        result[i] = min[i] + (p[i] - min[i]).rem_euclid(size);
+                    } else if p[i] < min[i] || p[i] > max[i] {
+                        return None;
+                    }
+                }
+                Some(result)
+            }
+        }
+    }
+
+    // Moves from p by d. Returns the new position and direction, which only
+    // a custom edge mapping changes.
+    pub fn step(
+        &self,
+        p: Point<isize, N>,
+        d: Point<isize, N>,
+    ) -> Option<(Point<isize, N>, Point<isize, N>)> {
+        match self {
+            Self::Custom { min, max, edge } => {
+                let next = p + d;
+                if Self::in_box(&next, min, max) {
+                    Some((next, d))
+                } else {
+                    edge(p, d)
+                }
+            }
+            _ => self.normalize(p + d).map(|next| (next, d)),
+        }
+    }
+
+    // The positions visited by repeatedly stepping in a direction, starting
+    // with `start`. Ends when leaving the grid, never on a torus.
+    pub fn walk(
+        &self,
+        start: Point<isize, N>,
+        direction: Point<isize, N>,
+    ) -> impl Iterator<Item = Point<isize, N>> + '_ {
+        let mut state = self.normalize(start).map(|p| (p, direction));
+        std::iter::from_fn(move || {
+            let (p, d) = state?;
+            state = self.step(p, d);
+            Some(p)
+        })
+    }
+}
+
+impl<const N: usize, U> PointGrid<isize, N, U> {
+    pub fn get_in(&self, topology: &Topology<N>, p: &Point<isize, N>) -> Option<&U> {
+        topology.normalize(*p).and_then(|p| self.get(&p))
+    }
+
+    // the cells one step away in each direction, as seen through the topology
+    pub fn neighbours_in<'a>(
+        &'a self,
+        topology: &'a Topology<N>,
+        p: Point<isize, N>,
+        directions: &'a [Point<isize, N>],
+    ) -> impl Iterator<Item = (Point<isize, N>, &'a U)> + 'a {
+        directions.iter().filter_map(move |d| {
+            let (q, _) = topology.step(p, *d)?;
+            self.get(&q).map(|u| (q, u))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::{Aabb, Point2};
+
+    fn grid() -> PointGrid<isize, 2, char> {
+        PointGrid::parse_with("abc\ndef\nghi", Some).unwrap()
+    }
+
+    fn neighbours(
+        grid: &PointGrid<isize, 2, char>,
+        topology: &Topology<2>,
+        p: Point2<isize>,
+    ) -> String {
+        let mut result = grid
+            .neighbours_in(topology, p, &Point2::directions_with_diagonals())
+            .map(|(_, c)| *c)
+            .collect::<Vec<_>>();
+        result.sort();
+        result.into_iter().collect()
+    }
+
+    #[test]
+    fn test_bounded_and_unbounded() {
+        let grid = grid();
+        let Aabb { min, max } = grid.dimensions().unwrap();
+        let bounded = Topology::Bounded { min, max };
+        assert_eq!(neighbours(&grid, &bounded, Point2::new(0, 0)), "bde");
+        assert_eq!(grid.get_in(&bounded, &Point2::new(3, 0)), None);
+        assert_eq!(
+            neighbours(&grid, &Topology::Unbounded, Point2::new(0, 0)),
+            "bde"
+        );
+        assert!(Topology::Unbounded.contains(&Point2::new(-100, 100)));
+        assert_eq!(
+            bounded.walk(Point2::new(0, 0), Point2::new(1, 1)).count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let grid = grid();
+        let Aabb { min, max } = grid.dimensions().unwrap();
+        let cylinder = Topology::cylinder(min, max, 0).unwrap();
+        assert_eq!(grid.get_in(&cylinder, &Point2::new(-1, 1)), Some(&'f'));
+        assert_eq!(grid.get_in(&cylinder, &Point2::new(7, 2)), Some(&'h'));
+        assert_eq!(grid.get_in(&cylinder, &Point2::new(0, 3)), None);
+        assert_eq!(neighbours(&grid, &cylinder, Point2::new(0, 0)), "bcdef");
+
+        let torus = Topology::torus(min, max).unwrap();
+        assert_eq!(neighbours(&grid, &torus, Point2::new(0, 0)), "bcdefghi");
+        let walked = torus
+            .walk(Point2::new(0, 0), Point2::new(1, 2))
+            .take(4)
+            .map(|p| grid.get(&p).unwrap())
+            .collect::<String>();
+        assert_eq!(walked, "ahfa");
+
+        // an empty box has no other side to wrap around to
+        assert!(Topology::torus(max, min).is_none());
+        assert!(Topology::cylinder(Point2::new(0, 2), Point2::new(3, 1), 0).is_none());
+        let empty = Topology::Wrapping {
+            min: max,
+            max: min,
+            wrap: [true, true],
+        };
+        assert_eq!(empty.normalize(Point2::new(0, 0)), None);
+        assert_eq!(empty.walk(Point2::new(0, 0), Point2::new(1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_custom_edges() {
+        // leaving the square to the east enters it from the top, heading
+        // south, in the column matching the row that was left
+        let min = Point2::new(0, 0);
+        let max = Point2::new(2, 2);
+        let topology = Topology::custom(
+            min,
+            max,
+            Box::new(|p: Point2<isize>, d: Point2<isize>| {
+                (d == Point2::new(1, 0)).then(|| (Point2::new(2 - p[1], 0), Point2::new(0, 1)))
+            }),
+        );
+        let path = topology
+            .walk(Point2::new(1, 1), Point2::new(1, 0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            path,
+            vec![
+                Point2::new(1, 1),
+                Point2::new(2, 1),
+                Point2::new(1, 0),
+                Point2::new(1, 1),
+                Point2::new(1, 2)
+            ]
+        );
+        assert_eq!(topology.step(Point2::new(0, 0), Point2::new(-1, 0)), None);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::{DenseGrid, Point, PointGrid, Scalar};
+
+// Looking along a straight line through a grid, e.g. which seat can be seen
+// from another one across the floor.
+
+// For every cell, the cells it sees. Building it once saves walking the
+// same rays again on every step of an automaton, as long as the transparent
+// cells don't change.
+pub type VisibilityGraph<T, const N: usize> = HashMap<Point<T, N>, Vec<Point<T, N>>>;
+
+// The cells at from + direction, from + 2 * direction, ... up to the first
+// point lookup doesn't find. Panics for a zero direction, which would never
+// leave from.
+fn ray<'a, T: Scalar + Copy + 'a, const N: usize, U: 'a>(
+    from: Point<T, N>,
+    direction: Point<T, N>,
+    lookup: impl Fn(&Point<T, N>) -> Option<&'a U> + 'a,
+) -> impl Iterator<Item = (Point<T, N>, &'a U)> + 'a {
+    assert!(
+        direction != Point::zero(),
+        "a ray needs a non-zero direction"
+    );
+    let mut current = from;
+    std::iter::from_fn(move || {
+        current += direction;
+        lookup(&current).map(|u| (current, u))
+    })
+}
+
+// the first cell along the ray that can't be seen through
+fn first_visible<'a, T: Scalar + Copy + 'a, const N: usize, U: 'a>(
+    from: Point<T, N>,
+    direction: Point<T, N>,
+    lookup: impl Fn(&Point<T, N>) -> Option<&'a U> + 'a,
+    is_transparent: impl Fn(&U) -> bool,
+) -> Option<(Point<T, N>, &'a U)> {
+    ray(from, direction, lookup).find(|(_, u)| !is_transparent(u))
+}
+
+impl<T: Scalar + Copy + Hash + Eq, const N: usize, U> PointGrid<T, N, U> {
+    pub fn ray(
+        &self,
+        from: Point<T, N>,
+        direction: Point<T, N>,
+    ) -> impl Iterator<Item = (Point<T, N>, &U)> {
+        ray(from, direction, |p| self.get(p))
+    }
+
+    pub fn first_visible(
+        &self,
+        from: Point<T, N>,
+        direction: Point<T, N>,
+        is_transparent: impl Fn(&U) -> bool,
+    ) -> Option<(Point<T, N>, &U)> {
+        first_visible(from, direction, |p| self.get(p), is_transparent)
+    }
+
+    // Which cells each opaque cell sees in the given directions.
+    // Transparent cells are left out, they don't look.
+    pub fn visibility_graph(
+        &self,
+        directions: &[Point<T, N>],
+        is_transparent: impl Fn(&U) -> bool,
+    ) -> VisibilityGraph<T, N> {
+        self.iter()
+            .filter(|(_, u)| !is_transparent(u))
+            .map(|(p, _)| {
+                let visible = directions
+                    .iter()
+                    .filter_map(|d| self.first_visible(*p, *d, &is_transparent))
+                    .map(|(q, _)| q)
+                    .collect();
+                (*p, visible)
+            })
+            .collect()
+    }
+}
+
+impl<U, const N: usize> DenseGrid<U, N> {
+    pub fn ray(
+        &self,
+        from: Point<isize, N>,
+        direction: Point<isize, N>,
+    ) -> impl Iterator<Item = (Point<isize, N>, &U)> {
+        ray(from, direction, |p| self.get(p))
+    }
+
+    pub fn first_visible(
+        &self,
+        from: Point<isize, N>,
+        direction: Point<isize, N>,
+        is_transparent: impl Fn(&U) -> bool,
+    ) -> Option<(Point<isize, N>, &U)> {
+        first_visible(from, direction, |p| self.get(p), is_transparent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::Point2;
+
+    fn grid() -> PointGrid<isize, 2, char> {
+        PointGrid::parse_with("#..#\n....\n.#.#", Some).unwrap()
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = grid();
+        let ray = grid
+            .ray(Point2::new(0, 0), Point2::new(1, 0))
+            .map(|(p, c)| (p[0], *c))
+            .collect::<Vec<_>>();
+        assert_eq!(ray, vec![(1, '.'), (2, '.'), (3, '#')]);
+        assert_eq!(grid.ray(Point2::new(3, 2), Point2::new(1, 1)).count(), 0);
+
+        let first = grid.first_visible(Point2::new(0, 0), Point2::new(1, 1), |c| *c == '.');
+        assert_eq!(first, None);
+        let first = grid.first_visible(Point2::new(3, 0), Point2::new(-1, 1), |c| *c == '.');
+        assert_eq!(first, Some((Point2::new(1, 2), &'#')));
+
+        let dense = DenseGrid::from_point_grid(grid, ' ');
+        let first = dense.first_visible(Point2::new(3, 2), Point2::new(-1, 0), |c| *c == '.');
+        assert_eq!(first, Some((Point2::new(1, 2), &'#')));
+        assert_eq!(dense.ray(Point2::new(0, 0), Point2::new(0, 1)).count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero direction")]
+    fn test_zero_direction() {
+        grid().first_visible(Point2::new(1, 1), Point2::zero(), |c| *c == '.');
+    }
+
+    #[test]
+    fn test_visibility_graph() {
+        let graph = grid().visibility_graph(&Point2::directions_with_diagonals(), |c| *c == '.');
+        assert_eq!(graph.len(), 4);
+        let mut seen = graph[&Point2::new(3, 0)].clone();
+        seen.sort_by_key(|p| (p[0], p[1]));
+        assert_eq!(
+            seen,
+            vec![Point2::new(0, 0), Point2::new(1, 2), Point2::new(3, 2)]
+        );
+        assert_eq!(graph[&Point2::new(1, 2)].len(), 2);
+    }
+}
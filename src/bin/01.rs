@@ -1,27 +1,18 @@
-use itertools::Itertools;
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let numbers = input.lines().map(|l| l.parse::<u32>().unwrap());
-    let mut combinations = numbers.combinations(2);
-    Some(
-        combinations
-            .find(|a| a.iter().sum::<u32>() == 2020)
-            .unwrap()
-            .iter()
-            .product(),
-    )
+use advent_of_code::helpers::ksum::k_sum;
+
+// the product of the k entries that add up to target
+pub fn find_product(input: &str, k: usize, target: i64) -> Option<i64> {
+    let numbers: Vec<i64> = input.lines().map(|l| l.parse().unwrap()).collect();
+    let picked = k_sum(&numbers, k, target)?;
+    Some(picked.iter().map(|i| numbers[*i]).product())
+}
+
+pub fn part_one(input: &str) -> Option<i64> {
+    find_product(input, 2, 2020)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let numbers = input.lines().map(|l| l.parse::<u32>().unwrap());
-    let mut combinations = numbers.combinations(3);
-    Some(
-        combinations
-            .find(|a| a.iter().sum::<u32>() == 2020)
-            .unwrap()
-            .iter()
-            .product(),
-    )
+pub fn part_two(input: &str) -> Option<i64> {
+    find_product(input, 3, 2020)
 }
 
 fn main() {
@@ -45,4 +36,12 @@ mod tests {
         let input = advent_of_code::read_file("examples", 1);
         assert_eq!(part_two(&input), Some(241861950));
     }
+
+    #[test]
+    fn test_other_targets() {
+        let input = advent_of_code::read_file("examples", 1);
+        assert_eq!(find_product(&input, 2, 1345), Some(979 * 366));
+        assert_eq!(find_product(&input, 4, 3000), None);
+        assert_eq!(find_product(&input, 4, 2319), Some(979 * 366 * 299 * 675));
+    }
 }
@@ -80,7 +80,6 @@ pub fn part_one(input: &str) -> Option<u32> {
         input
             .lines()
             .flat_map(parser::parse_line)
-            .into_iter()
             .map(|(policy, password)| policy.is_password_valid_sled(password))
             .filter(|b| *b)
             .count()
@@ -94,7 +93,6 @@ pub fn part_two(input: &str) -> Option<u32> {
         input
             .lines()
             .flat_map(parser::parse_line)
-            .into_iter()
             .map(|(policy, password)| policy.is_password_valid_toboggan(password))
             .filter(|b| *b)
             .count()
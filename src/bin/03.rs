@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use itertools::Itertools;
+use advent_of_code::algebra_helpers::{Aabb, Point2, PointGrid, Topology};
 
 enum Location {
     Empty,
@@ -25,25 +25,26 @@ struct Slope {
 }
 
 struct Map {
-    grid: Vec<Vec<Location>>,
+    grid: PointGrid<isize, 2, Location>,
 }
 
 impl From<&str> for Map {
     fn from(value: &str) -> Self {
         Map {
-            grid: value
-                .lines()
-                .map(|x| x.chars().map(|x| x.try_into().unwrap()).collect_vec())
-                .collect_vec(),
+            grid: PointGrid::parse_with(value, |c| c.try_into().ok()).unwrap(),
         }
     }
 }
 
 impl Display for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for line in self.grid.iter() {
-            for loc in line {
-                let char_s = match loc {
+        let max = self
+            .grid
+            .dimensions()
+            .map_or(Point2::new(-1, -1), |b| b.max);
+        for y in 0..=max.0[1] {
+            for x in 0..=max.0[0] {
+                let char_s = match self.grid.get(&Point2::new(x, y)).unwrap() {
                     Location::Empty => '.',
                     Location::Tree => '#',
                 };
@@ -56,22 +57,21 @@ impl Display for Map {
 }
 
 impl Map {
+    // the map repeats to the right, so it is a cylinder
     fn count_trees_on_slope(&self, slope: Slope) -> u32 {
-        let mut pos = (0, 0);
-        let size = (self.grid.get(0).unwrap().len(), self.grid.len());
-        let mut trees = 0;
-
-        while pos.1 < size.1 {
-            if let Location::Tree = self.grid.get(pos.1).unwrap().get(pos.0).unwrap() {
-                trees += 1;
-            }
-
-            pos.0 += slope.x_offset as usize;
-            pos.0 %= size.0;
-            pos.1 += slope.y_offset as usize;
-        }
-
-        trees
+        let Some(topology) = self
+            .grid
+            .dimensions()
+            .and_then(|Aabb { min, max }| Topology::cylinder(min, max, 0))
+        else {
+            return 0;
+        };
+        let step = Point2::new(slope.x_offset as isize, slope.y_offset as isize);
+
+        topology
+            .walk(Point2::new(0, 0), step)
+            .filter(|p| matches!(self.grid.get(p), Some(Location::Tree)))
+            .count() as u32
     }
 }
 
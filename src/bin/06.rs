@@ -1,53 +1,54 @@
-use std::collections::HashSet;
+#![cfg_attr(test, feature(test))]
 
-use itertools::Itertools;
+use advent_of_code::helpers::bitset::Bitset;
 
 mod parser {
     use nom::{
-        character::complete::{alpha1, line_ending},
+        bytes::complete::take_while1,
+        character::complete::{line_ending, multispace0},
+        combinator::all_consuming,
         error::Error as NomError,
         multi::separated_list1,
-        sequence::pair,
+        sequence::{pair, terminated},
         Finish, IResult,
     };
 
     pub fn parse_groups(s: &str) -> Result<Vec<Vec<&str>>, NomError<&str>> {
-        let (_, x) = separated_list1(pair(line_ending, line_ending), parse_group)(s).finish()?;
+        let groups = separated_list1(pair(line_ending, line_ending), parse_group);
+        let (_, x) = all_consuming(terminated(groups, multispace0))(s).finish()?;
         Ok(x)
     }
 
+    // questions are the lowercase letters a to z
     fn parse_group(s: &str) -> IResult<&str, Vec<&str>> {
-        separated_list1(line_ending, alpha1)(s)
+        separated_list1(line_ending, take_while1(|c: char| c.is_ascii_lowercase()))(s)
     }
 }
 
-pub fn find_intersection(group: Vec<&str>) -> Vec<char> {
-    match group.len() {
-        0 => vec![],
-        1 => group[0].chars().collect_vec(),
-        _ => group[0]
-            .chars()
-            .filter(|c| group[1..].iter().all(|g| g.chars().contains(c)))
-            .collect_vec(),
-    }
+// the questions answered with yes, one bit per letter, only lowercase
+// letters get through the parser
+fn answers(person: &str) -> Bitset<1> {
+    person.bytes().map(|b| (b - b'a') as usize).collect()
 }
 
-pub fn find_union(group: Vec<&str>) -> Vec<char> {
-    let mut set = HashSet::new();
-
-    for cs in group {
-        set.extend(cs.chars());
-    }
+pub fn find_intersection(group: &[&str]) -> Bitset<1> {
+    group
+        .iter()
+        .map(|p| answers(p))
+        .reduce(|acc, a| acc & a)
+        .unwrap_or_default()
+}
 
-    set.into_iter().collect_vec()
+pub fn find_union(group: &[&str]) -> Bitset<1> {
+    group.iter().fold(Bitset::new(), |acc, p| acc | answers(p))
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     Some(
         parser::parse_groups(input)
-            .unwrap()
+            .ok()?
             .into_iter()
-            .map(|g| find_union(g).len() as u32)
+            .map(|g| find_union(&g).count() as u32)
             .sum(),
     )
 }
@@ -55,9 +56,9 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u32> {
     Some(
         parser::parse_groups(input)
-            .unwrap()
+            .ok()?
             .into_iter()
-            .map(|g| find_intersection(g).len() as u32)
+            .map(|g| find_intersection(&g).count() as u32)
             .sum(),
     )
 }
@@ -70,7 +71,87 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    extern crate test;
+
     use super::*;
+    use itertools::Itertools;
+    use std::collections::HashSet;
+    use test::Bencher;
+
+    // char and HashSet answers to compare the bitsets against
+    fn find_intersection_chars(group: &[&str]) -> Vec<char> {
+        group[0]
+            .chars()
+            .filter(|c| group[1..].iter().all(|g| g.chars().contains(c)))
+            .collect_vec()
+    }
+
+    fn find_union_chars(group: &[&str]) -> Vec<char> {
+        let mut set = HashSet::new();
+        for cs in group {
+            set.extend(cs.chars());
+        }
+        set.into_iter().collect_vec()
+    }
+
+    fn groups() -> Vec<Vec<String>> {
+        let input = advent_of_code::read_file("examples", 6);
+        parser::parse_groups(&input)
+            .unwrap()
+            .into_iter()
+            .map(|g| g.into_iter().map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_groups() {
+        let counts = groups()
+            .iter()
+            .map(|group| {
+                let group = group.iter().map(String::as_str).collect_vec();
+                (
+                    find_union(&group).count(),
+                    find_intersection(&group).count(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(counts, vec![(3, 3), (3, 0), (3, 1), (1, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_only_lowercase() {
+        assert_eq!(part_one("abc\n\nab\n"), Some(5));
+        assert_eq!(part_one("abc\n\naB\n"), None);
+        assert_eq!(part_two("a1\n"), None);
+    }
+
+    #[bench]
+    fn bench_chars(b: &mut Bencher) {
+        let groups = groups();
+        b.iter(|| {
+            groups
+                .iter()
+                .map(|g| {
+                    let g = g.iter().map(String::as_str).collect_vec();
+                    find_union_chars(&g).len() + find_intersection_chars(&g).len()
+                })
+                .sum::<usize>()
+        });
+    }
+
+    #[bench]
+    fn bench_bitset(b: &mut Bencher) {
+        let groups = groups();
+        b.iter(|| {
+            groups
+                .iter()
+                .map(|g| {
+                    let g = g.iter().map(String::as_str).collect_vec();
+                    find_union(&g).count() + find_intersection(&g).count()
+                })
+                .sum::<usize>()
+        });
+    }
 
     #[test]
     fn test_part_one() {
@@ -1,5 +1,5 @@
-use std::collections::{HashMap, HashSet};
-
+use advent_of_code::helpers::dot::Dot;
+use advent_of_code::helpers::graph::{Graph, Traversable};
 use itertools::Itertools;
 
 #[derive(Debug, PartialEq)]
@@ -21,46 +21,22 @@ impl From<(&str, Vec<(&str, &str)>)> for Rule {
     }
 }
 
-fn find_bag_rules_containing(rules: &Vec<Rule>, mut bag_types: HashSet<String>) -> HashSet<String> {
+// edges from every bag to the bags directly inside it, weighted by the amount
+fn containment_graph(rules: &[Rule]) -> Graph<String, u32> {
+    let mut graph = Graph::new();
     for rule in rules {
-        if rule.contained.iter().any(|(_, bt)| bag_types.contains(bt)) {
-            bag_types.insert(rule.outer.clone());
+        graph.add_node(rule.outer.clone());
+        for (n, bt) in &rule.contained {
+            graph.add_edge(rule.outer.clone(), bt.clone(), *n);
         }
     }
-    bag_types
+    graph
 }
 
-fn calculate_next_level_of_bags(
-    rules: &Vec<Rule>,
-    mut bag_map: HashMap<String, u32>,
-) -> HashMap<String, u32> {
-    for rule in rules {
-        if bag_map.contains_key(&rule.outer) {
-            continue;
-        }
-
-        if rule.contained.is_empty() {
-            bag_map.insert(rule.outer.clone(), 1);
-            continue;
-        }
-
-        if rule
-            .contained
-            .iter()
-            .all(|(_, bt)| bag_map.contains_key(bt))
-        {
-            bag_map.insert(
-                rule.outer.clone(),
-                rule.contained
-                    .iter()
-                    .map(|(n, bt)| n * bag_map.get(bt).unwrap())
-                    .sum::<u32>()
-                    + 1,
-            );
-        }
-    }
-
-    bag_map
+// bags with arrows to what they contain, labelled with the amount
+pub fn to_dot(input: &str) -> Dot {
+    let rules = parser::parse_rules(input).unwrap();
+    containment_graph(&rules).to_dot("bags")
 }
 
 mod parser {
@@ -77,6 +53,8 @@ mod parser {
 
     use crate::Rule;
 
+    type RuleComponents<'a> = (&'a str, Vec<(&'a str, &'a str)>);
+
     pub fn parse_rules(s: &str) -> Result<Vec<Rule>, NomError<&str>> {
         let (_, x) = separated_list1(line_ending, parse_rule)(s).finish()?;
         Ok(x)
@@ -86,7 +64,7 @@ mod parser {
         into(parse_rule_components)(s)
     }
 
-    pub fn parse_rule_components(s: &str) -> IResult<&str, (&str, Vec<(&str, &str)>)> {
+    pub fn parse_rule_components(s: &str) -> IResult<&str, RuleComponents<'_>> {
         separated_pair(parse_bag, tag(" contain "), parse_bag_list)(s)
     }
 
@@ -120,34 +98,29 @@ mod parser {
 pub fn part_one(input: &str) -> Option<u32> {
     let rules = parser::parse_rules(input).unwrap();
     let my_bag = String::from("shiny gold");
-    let mut bag_types_containing =
-        find_bag_rules_containing(&rules, HashSet::from([my_bag.clone()]));
-    let mut bag_old = HashSet::new();
-    bag_types_containing.remove(&my_bag);
-
-    while bag_types_containing != bag_old {
-        bag_old = bag_types_containing.clone();
-        bag_types_containing = find_bag_rules_containing(&rules, bag_types_containing);
-    }
-    Some(bag_types_containing.len() as u32)
+    let containing = containment_graph(&rules).reversed().reachable(&my_bag);
+
+    Some(containing.len() as u32 - 1)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let rules = parser::parse_rules(input).unwrap();
 
     let my_bag = String::from("shiny gold");
-    let mut bag_map = calculate_next_level_of_bags(&rules, HashMap::new());
-
-    while !bag_map.contains_key(&my_bag) {
-        bag_map = calculate_next_level_of_bags(&rules, bag_map);
-    }
-    Some(*bag_map.get(&my_bag).unwrap() - 1)
+    // counts the bag itself as well
+    let bags = containment_graph(&rules)
+        .fold_dag(&my_bag, |_, inside: &[(u32, u32)]| {
+            1 + inside.iter().map(|(n, bags)| n * bags).sum::<u32>()
+        })
+        .ok()?;
+    Some(bags - 1)
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 7);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::solve!(dot, 7, to_dot, input);
 }
 
 #[cfg(test)]
@@ -177,6 +150,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_dot() {
+        let dot = to_dot(&advent_of_code::read_file("examples", 7)).to_string();
+        assert!(dot.starts_with("digraph \"bags\" {"));
+        assert!(dot.contains("\"shiny gold\" -> \"dark olive\" [label=\"1\"];"));
+        assert!(dot.contains("\"faded blue\" [label=\"faded blue\"];"));
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 7);
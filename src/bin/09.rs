@@ -1,19 +1,9 @@
+use advent_of_code::helpers::ksum::{contiguous_sum, first_invalid};
 use itertools::Itertools;
 
-pub fn find_wrong_number(numbers: &Vec<i64>, preamble: usize) -> Option<i64> {
-    for (i, n) in numbers.iter().skip(preamble).enumerate() {
-        let numbers_allowed = numbers.iter().skip(i).take(preamble);
-        if numbers_allowed.clone().all(|x| {
-            if (n - x) == *x {
-                true
-            } else {
-                !numbers_allowed.clone().contains(&(*n - *x))
-            }
-        }) {
-            return Some(*n);
-        }
-    }
-    None
+// the first number that isn't the sum of two of the `preamble` before it
+pub fn find_wrong_number(numbers: &[i64], preamble: usize) -> Option<i64> {
+    first_invalid(numbers, preamble).map(|i| numbers[i])
 }
 
 pub fn part_one(input: &str) -> Option<i64> {
@@ -24,26 +14,11 @@ pub fn part_one(input: &str) -> Option<i64> {
     find_wrong_number(&numbers, 25)
 }
 
-pub fn find_weakness(numbers: &Vec<i64>, preamble: usize) -> Option<i64> {
-    let wrong_number = find_wrong_number(numbers, preamble).unwrap();
-
-    'outer: for (start_index, _) in numbers.iter().enumerate() {
-        let mut running_total = 0;
-        let mut smallest_n = i64::MAX;
-        let mut largest_n = i64::MIN;
-
-        for current_n in numbers.iter().skip(start_index) {
-            running_total += current_n;
-            smallest_n = smallest_n.min(*current_n);
-            largest_n = largest_n.max(*current_n);
-            match running_total.cmp(&wrong_number) {
-                std::cmp::Ordering::Equal => return Some(smallest_n + largest_n),
-                std::cmp::Ordering::Greater => continue 'outer,
-                std::cmp::Ordering::Less => (),
-            }
-        }
-    }
-    None
+pub fn find_weakness(numbers: &[i64], preamble: usize) -> Option<i64> {
+    let wrong_number = find_wrong_number(numbers, preamble)?;
+    let range = contiguous_sum(numbers, wrong_number, 2)?;
+    let (smallest, largest) = numbers[range].iter().minmax().into_option()?;
+    Some(smallest + largest)
 }
 
 pub fn part_two(input: &str) -> Option<i64> {
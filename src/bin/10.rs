@@ -1,76 +1,166 @@
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code::helpers::bigint::BigUint;
 use itertools::Itertools;
+use num_traits::{One, Zero};
 
-pub fn get_sorted_adapter_list(input: &str) -> Vec<u32> {
+// The outlet (0), the sorted adapters and the device, which is rated
+// `max_step` jolts above the highest adapter.
+pub fn get_sorted_adapter_list(input: &str, max_step: u32) -> Vec<u32> {
     let mut adapters_list = input
         .lines()
         .map(|x| x.parse::<u32>().unwrap())
         .collect_vec();
 
     adapters_list.push(0);
-    adapters_list.push(adapters_list.iter().max().unwrap() + 3);
+    adapters_list.push(adapters_list.iter().max().unwrap() + max_step);
     adapters_list.sort();
     adapters_list
 }
 
-pub fn split_into_isolated_lists(input_list: Vec<u32>) -> Vec<Vec<u32>> {
-    let mut results = vec![];
-    let mut current_list = vec![];
+pub fn is_valid_adapter_chain(input_list: &[u32], max_step: u32) -> bool {
+    match input_list.len() {
+        0 | 1 => false, // should not happen
+        _ => input_list
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a <= b && b - a <= max_step),
+    }
+}
+
+// The ways to pick adapters from a sorted list so that every one takes the
+// previous one, at most `max_step` jolts apart. The first and the last
+// element (outlet and device) are always part of the chain.
+#[derive(Debug, Clone)]
+pub struct Arrangements<'a> {
+    adapters: &'a [u32],
+    max_step: u32,
+}
 
-    for (a, b) in input_list.iter().tuple_windows() {
-        current_list.push(*a);
-        if b - a == 3 {
-            results.push(current_list);
-            current_list = vec![];
+impl<'a> Arrangements<'a> {
+    pub fn new(adapters: &'a [u32], max_step: u32) -> Self {
+        assert!(adapters.is_sorted(), "adapters must be sorted");
+        Self { adapters, max_step }
+    }
+
+    // the adapters after i that can take adapter i
+    fn successors(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let joltage = self.adapters[i];
+        (i + 1..self.adapters.len())
+            .take_while(move |j| self.adapters[*j] - joltage <= self.max_step)
+    }
+
+    // For every adapter the number of chains from it to the device, from the
+    // last one back. None if `add` fails.
+    fn counts_to_end<C: Clone + Zero + One>(
+        &self,
+        add: impl Fn(&C, &C) -> Option<C>,
+    ) -> Option<Vec<C>> {
+        let n = self.adapters.len();
+        let mut counts = vec![C::zero(); n];
+        if n == 0 {
+            return Some(counts);
         }
+        counts[n - 1] = C::one();
+        for i in (0..n - 1).rev() {
+            let mut count = C::zero();
+            for j in self.successors(i) {
+                count = add(&count, &counts[j])?;
+            }
+            counts[i] = count;
+        }
+        Some(counts)
     }
 
-    results
-}
+    pub fn count(&self) -> BigUint {
+        let counts = self.counts_to_end(|a: &BigUint, b| Some(a + b)).unwrap();
+        counts.first().cloned().unwrap_or_default()
+    }
 
-pub fn calculate_combinations_of_list(input_list: Vec<u32>) -> u32 {
-    match input_list.len() {
-        0 => return 0, // should not happen
-        1 | 2 => return 1,
-        _ => {}
-    }
-
-    // first and last element are fixed in a list. Create all combinations of elements between them.
-    let mut combinations = 0;
-    for x in input_list
-        .iter()
-        .skip(1)
-        .take(input_list.len() - 2)
-        .powerset()
-    {
-        // recreate the combination list with first and last element from original list.
-        let mut intermediate_list = vec![input_list[0]];
-        intermediate_list.append(&mut x.iter().map(|n| **n).collect_vec());
-        intermediate_list.push(*input_list.last().unwrap());
-
-        if is_valid_adapter_chain(intermediate_list) {
-            combinations += 1;
+    // None if the count doesn't fit into u64
+    pub fn count_checked(&self) -> Option<u64> {
+        let counts = self.counts_to_end(|a: &u64, b| a.checked_add(*b))?;
+        Some(counts.first().copied().unwrap_or(0))
+    }
+
+    // up to `limit` valid chains, in lexicographic order
+    pub fn chains(&self, limit: usize) -> Vec<Vec<u32>> {
+        let mut result = vec![];
+        if self.adapters.is_empty() {
+            return result;
         }
+        // only the count being zero or not matters here
+        let counts = self
+            .counts_to_end(|a: &u8, b| Some((a | b).min(1)))
+            .unwrap();
+        let mut chain = vec![0];
+        self.extend_chains(&counts, &mut chain, limit, &mut result);
+        result
     }
 
-    combinations
-}
+    fn extend_chains(
+        &self,
+        counts: &[u8],
+        chain: &mut Vec<usize>,
+        limit: usize,
+        result: &mut Vec<Vec<u32>>,
+    ) {
+        let last = *chain.last().unwrap();
+        if counts[last] == 0 || result.len() >= limit {
+            return;
+        }
+        if last == self.adapters.len() - 1 {
+            result.push(chain.iter().map(|i| self.adapters[*i]).collect());
+            return;
+        }
+        for j in self.successors(last).collect_vec() {
+            chain.push(j);
+            self.extend_chains(counts, chain, limit, result);
+            chain.pop();
+        }
+    }
 
-pub fn is_valid_adapter_chain(input_list: Vec<u32>) -> bool {
-    match input_list.len() {
-        0 | 1 => false, // should not happen
-        _ => {
-            for (a, b) in input_list.iter().tuple_windows() {
-                if b - a > 3 {
-                    return false;
+    // One valid chain, picked uniformly at random among all of them, None if
+    // there is none. `random` gives evenly distributed u64 values.
+    pub fn sample(&self, mut random: impl FnMut() -> u64) -> Option<Vec<u32>> {
+        // exact counts, floats would skew the choice beyond 2^53 chains
+        let counts = self.counts_to_end(|a: &BigUint, b| Some(a + b))?;
+        if counts.first().is_none_or(|c| c.is_zero()) {
+            return None;
+        }
+        let mut i = 0;
+        let mut chain = vec![self.adapters[0]];
+        while i < self.adapters.len() - 1 {
+            let mut target = random_below(&counts[i], &mut random);
+            let candidates = self
+                .successors(i)
+                .filter(|j| !counts[*j].is_zero())
+                .collect_vec();
+            i = *candidates.last().unwrap();
+            for j in candidates {
+                if target < counts[j] {
+                    i = j;
+                    break;
                 }
+                target -= counts[j].clone();
             }
-            true
+            chain.push(self.adapters[i]);
         }
+        Some(chain)
     }
 }
 
+// Evenly distributed in 0..n, up to a bias below 2^-64 from the extra word.
+fn random_below(n: &BigUint, random: &mut impl FnMut() -> u64) -> BigUint {
+    let word = BigUint::from(1u128 << 64);
+    let r = (0..n.bits() / 64 + 2).fold(BigUint::zero(), |acc, _| {
+        acc * word.clone() + BigUint::from(random())
+    });
+    r % n.clone()
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let adapters_list = get_sorted_adapter_list(input);
+    let adapters_list = get_sorted_adapter_list(input, 3);
 
     let mut result_1 = 0;
     let mut result_3 = 0;
@@ -84,15 +174,9 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(result_1 * result_3)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let adapters_list = get_sorted_adapter_list(input);
-
-    let mut combinations_per_sublist = vec![];
-    for sublist in split_into_isolated_lists(adapters_list) {
-        combinations_per_sublist.push(calculate_combinations_of_list(sublist) as u64);
-    }
-
-    Some(combinations_per_sublist.iter().product())
+pub fn part_two(input: &str) -> Option<BigUint> {
+    let adapters_list = get_sorted_adapter_list(input, 3);
+    Some(Arrangements::new(&adapters_list, 3).count())
 }
 
 fn main() {
@@ -103,7 +187,27 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    extern crate test;
+
     use super::*;
+    use advent_of_code::helpers::random::Random;
+    use std::collections::HashSet;
+    use test::Bencher;
+
+    // runs of adapters one jolt apart with gaps of three between them
+    fn long_input(runs: usize) -> String {
+        let mut random = Random::new(3);
+        let mut joltage = 0;
+        let mut lines = vec![];
+        for _ in 0..runs {
+            joltage += 2;
+            for _ in 0..random.below(5) + 1 {
+                joltage += 1;
+                lines.push(joltage.to_string());
+            }
+        }
+        lines.join("\n")
+    }
 
     #[test]
     fn test_part_one() {
@@ -114,6 +218,90 @@ mod tests {
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 10);
-        assert_eq!(part_two(&input), Some(8));
+        assert_eq!(part_two(&input), Some(BigUint::from(8u64)));
+    }
+
+    #[test]
+    fn test_larger_example() {
+        let input = "28\n33\n18\n42\n31\n14\n46\n20\n48\n47\n24\n23\n49\n45\n19\n38\n39\n\
+                     11\n1\n32\n25\n35\n8\n17\n7\n9\n4\n2\n34\n10\n3";
+        assert_eq!(part_one(input), Some(220));
+        assert_eq!(part_two(input), Some(BigUint::from(19208u64)));
+    }
+
+    #[test]
+    #[should_panic(expected = "must be sorted")]
+    fn test_unsorted() {
+        Arrangements::new(&[0, 3, 1, 4], 3);
+    }
+
+    #[test]
+    fn test_sample_beyond_f64() {
+        // about 10^52 chains, every step still has to stay valid
+        let adapters = (0..200).collect_vec();
+        let arrangements = Arrangements::new(&adapters, 3);
+        let mut random = Random::new(7);
+        for _ in 0..5 {
+            let chain = arrangements.sample(|| random.next_u64()).unwrap();
+            assert!(is_valid_adapter_chain(&chain, 3));
+            assert_eq!((chain[0], *chain.last().unwrap()), (0, 199));
+        }
+    }
+
+    #[test]
+    fn test_max_step() {
+        let adapters = [0, 1, 2, 3, 4];
+        assert_eq!(Arrangements::new(&adapters, 1).count_checked(), Some(1));
+        assert_eq!(Arrangements::new(&adapters, 2).count_checked(), Some(5));
+        assert_eq!(Arrangements::new(&adapters, 4).count_checked(), Some(8));
+        // the device can't be reached at all
+        assert_eq!(Arrangements::new(&[0, 5, 6], 3).count_checked(), Some(0));
+        assert_eq!(Arrangements::new(&[0, 5, 6], 3).sample(|| 0), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        // tribonacci numbers, beyond u64 after about 75 adapters
+        let adapters = (0..200).collect_vec();
+        let arrangements = Arrangements::new(&adapters, 3);
+        assert_eq!(arrangements.count_checked(), None);
+        assert_eq!(
+            arrangements.count().to_string(),
+            "28610320653810477165032088685001500201865067503083660"
+        );
+        let short = (0..50).collect_vec();
+        assert_eq!(
+            Arrangements::new(&short, 3).count().to_string(),
+            Arrangements::new(&short, 3)
+                .count_checked()
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_chains() {
+        let input = advent_of_code::read_file("examples", 10);
+        let adapters = get_sorted_adapter_list(&input, 3);
+        let arrangements = Arrangements::new(&adapters, 3);
+        let chains = arrangements.chains(100);
+        assert_eq!(chains.len(), 8);
+        assert_eq!(chains[0], adapters);
+        assert_eq!(chains.iter().unique().count(), 8);
+        assert!(chains.iter().all(|c| is_valid_adapter_chain(c, 3)));
+        assert_eq!(arrangements.chains(3).len(), 3);
+
+        // sampling finds every chain of the few there are
+        let mut random = Random::new(11);
+        let sampled = (0..200)
+            .map(|_| arrangements.sample(|| random.next_u64()).unwrap())
+            .collect::<HashSet<_>>();
+        assert_eq!(sampled, chains.into_iter().collect());
+    }
+
+    #[bench]
+    fn bench_arrangements(b: &mut Bencher) {
+        let adapters = get_sorted_adapter_list(&long_input(15), 3);
+        b.iter(|| Arrangements::new(&adapters, 3).count_checked());
     }
 }
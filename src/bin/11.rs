@@ -1,7 +1,12 @@
-use advent_of_code::algebra_helpers::{Point2, PointGrid};
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code::algebra_helpers::{
+    Automaton, DenseGrid, Growth, Neighbourhood, Point2, PointGrid,
+};
+use advent_of_code::helpers::cycles::fixed_point;
 use parse_display::{Display, FromStr};
 
-#[derive(Display, FromStr, PartialEq, Debug)]
+#[derive(Display, FromStr, PartialEq, Debug, Clone, Copy)]
 pub enum Space {
     #[display(".")]
     Floor,
@@ -12,162 +17,108 @@ pub enum Space {
 }
 
 pub fn create_grid(input: &str) -> PointGrid<isize, 2, Space> {
-    let mut grid = PointGrid::default();
-
-    for (y, row) in input.lines().enumerate() {
-        for (x, character) in row.chars().enumerate() {
-            grid.insert(
-                Point2::new(x as isize, y as isize),
-                character.to_string().parse().unwrap(),
-            )
-        }
-    }
-
-    grid
+    PointGrid::parse(input).unwrap()
 }
 
-pub fn step_gol(
-    input: &PointGrid<isize, 2, Space>,
-    next_state_function: &dyn Fn(&Point2<isize>, &PointGrid<isize, 2, Space>) -> Space,
-) -> (PointGrid<isize, 2, Space>, bool) {
-    let mut new_grid = PointGrid::default();
-    let mut changed = false;
-    for pos in input.iter_full_bounds() {
-        let new_value = next_state_function(&pos, input);
-
-        if *input.get(&pos).unwrap() != new_value {
-            changed = true;
+// An empty seat gets taken if no occupied seat is around, an occupied one is
+// left if at least `tolerance` others are.
+pub fn seat_rule(tolerance: usize) -> impl Fn(&Space, &[&Space]) -> Space + Sync {
+    move |seat, neighbours| {
+        let occupied = neighbours
+            .iter()
+            .filter(|s| ***s == Space::Occupied)
+            .count();
+        match seat {
+            Space::Empty if occupied == 0 => Space::Occupied,
+            Space::Occupied if occupied >= tolerance => Space::Empty,
+            _ => *seat,
         }
-
-        new_grid.insert(pos, new_value);
     }
-
-    (new_grid, changed)
 }
 
-pub fn get_next_state_part1(pos: &Point2<isize>, grid: &PointGrid<isize, 2, Space>) -> Space {
-    match grid.get(pos) {
-        Some(Space::Floor) => Space::Floor,
-        Some(Space::Empty) => {
-            if Point2::<isize>::directions_with_diagonals()
-                .iter()
-                .filter_map(|d| grid.get(&(*pos + *d)))
-                .all(|s| *s == Space::Empty || *s == Space::Floor)
-            {
-                Space::Occupied
-            } else {
-                Space::Empty
-            }
-        }
-        Some(Space::Occupied) => {
-            if Point2::<isize>::directions_with_diagonals()
-                .iter()
-                .filter_map(|d| grid.get(&(*pos + *d)))
-                .filter(|s| **s == Space::Occupied)
-                .count()
-                >= 4
-            {
-                Space::Empty
-            } else {
-                Space::Occupied
-            }
-        }
-        None => unreachable!(),
-    }
+pub fn count_occupied(
+    grid: PointGrid<isize, 2, Space>,
+    neighbourhood: Neighbourhood<Space, 2>,
+    tolerance: usize,
+) -> u32 {
+    let mut automaton = Automaton::new(grid, neighbourhood, Growth::Fixed, seat_rule(tolerance));
+    automaton.run_until_stable();
+
+    automaton
+        .grid()
+        .values()
+        .filter(|v| **v == Space::Occupied)
+        .count() as u32
 }
 
-pub fn get_next_state_part2(pos: &Point2<isize>, grid: &PointGrid<isize, 2, Space>) -> Space {
-    match grid.get(pos) {
-        Some(Space::Floor) => Space::Floor,
-        Some(Space::Empty) => {
-            for d in Point2::<isize>::directions_with_diagonals().iter() {
-                let mut distance = 1;
-                loop {
-                    let s = grid.get(&(*pos + *d * distance));
-                    match s {
-                        Some(Space::Floor) => {
-                            distance += 1;
-                            continue;
-                        }
-                        Some(Space::Occupied) => {
-                            return Space::Empty;
-                        }
-                        Some(Space::Empty) => {
-                            break;
-                        }
-                        None => {
-                            break;
-                        }
-                    }
-                }
+// One round on a DenseGrid. Every cell is recomputed instead of only the ones
+// next to a change, but lookups are offsets into a Vec instead of hashes.
+pub fn step_dense(
+    grid: &DenseGrid<Space, 2>,
+    neighbourhood: &Neighbourhood<Space, 2>,
+    rule: &impl Fn(&Space, &[&Space]) -> Space,
+) -> DenseGrid<Space, 2> {
+    let mut neighbours = vec![];
+    grid.map(|p, seat| {
+        neighbours.clear();
+        match neighbourhood {
+            Neighbourhood::Offsets(offsets) => {
+                neighbours.extend(offsets.iter().filter_map(|d| grid.get(&(p + *d))));
             }
-            Space::Occupied
-        }
-        Some(Space::Occupied) => {
-            let mut occupied_seats = 0;
-            for d in Point2::<isize>::directions_with_diagonals().iter() {
-                let mut distance = 1;
-                loop {
-                    let s = grid.get(&(*pos + *d * distance));
-                    match s {
-                        Some(Space::Floor) => {
-                            distance += 1;
-                            continue;
-                        }
-                        Some(Space::Occupied) => {
-                            occupied_seats += 1;
-                            break;
-                        }
-                        Some(Space::Empty) => {
-                            break;
-                        }
-                        None => {
-                            break;
-                        }
-                    }
-                }
+            Neighbourhood::LineOfSight {
+                directions,
+                transparent,
+            } => {
+                neighbours.extend(
+                    directions
+                        .iter()
+                        .filter_map(|d| grid.first_visible(p, *d, transparent))
+                        .map(|(_, s)| s),
+                );
             }
-
-            if occupied_seats >= 5 {
-                Space::Empty
-            } else {
-                Space::Occupied
+            Neighbourhood::Graph {
+                neighbours: graph, ..
+            } => {
+                neighbours.extend(
+                    graph
+                        .get(&p)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|q| grid.get(q)),
+                );
             }
         }
-        None => unreachable!(),
-    }
+        rule(seat, &neighbours)
+    })
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut grid = create_grid(input);
-
-    let mut changed = true;
-    while changed {
-        (grid, changed) = step_gol(&grid, &get_next_state_part1);
-    }
+pub fn count_occupied_dense(
+    grid: PointGrid<isize, 2, Space>,
+    neighbourhood: Neighbourhood<Space, 2>,
+    tolerance: usize,
+) -> u32 {
+    let rule = seat_rule(tolerance);
+    let grid = fixed_point(DenseGrid::from_point_grid(grid, Space::Floor), |g| {
+        step_dense(g, &neighbourhood, &rule)
+    });
+
+    grid.values().filter(|v| **v == Space::Occupied).count() as u32
+}
 
-    Some(
-        grid.0
-            .iter()
-            .filter(|(_, v)| **v == Space::Occupied)
-            .count() as u32,
-    )
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(count_occupied(
+        create_grid(input),
+        Neighbourhood::moore(),
+        4,
+    ))
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut grid = create_grid(input);
-
-    let mut changed = true;
-    while changed {
-        (grid, changed) = step_gol(&grid, &get_next_state_part2);
-    }
-
-    Some(
-        grid.0
-            .iter()
-            .filter(|(_, v)| **v == Space::Occupied)
-            .count() as u32,
-    )
+    let grid = create_grid(input);
+    // the floor never changes, so everyone always sees the same seats
+    let visible =
+        grid.visibility_graph(&Point2::directions_with_diagonals(), |s| *s == Space::Floor);
+    Some(count_occupied(grid, Neighbourhood::graph(visible), 5))
 }
 
 fn main() {
@@ -178,7 +129,10 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    extern crate test;
+
     use super::*;
+    use test::Bencher;
 
     #[test]
     fn test_part_one() {
@@ -191,4 +145,69 @@ mod tests {
         let input = advent_of_code::read_file("examples", 11);
         assert_eq!(part_two(&input), Some(26));
     }
+
+    #[test]
+    fn test_dense_grid() {
+        let input = advent_of_code::read_file("examples", 11);
+        let grid = create_grid(&input);
+        assert_eq!(
+            count_occupied_dense(grid.clone(), Neighbourhood::moore(), 4),
+            37
+        );
+        let neighbourhood = Neighbourhood::line_of_sight(|s| *s == Space::Floor);
+        assert_eq!(count_occupied_dense(grid.clone(), neighbourhood, 5), 26);
+        let visible =
+            grid.visibility_graph(&Point2::directions_with_diagonals(), |s| *s == Space::Floor);
+        assert_eq!(
+            count_occupied_dense(grid, Neighbourhood::graph(visible), 5),
+            26
+        );
+    }
+
+    #[bench]
+    fn bench_automaton(b: &mut Bencher) {
+        let input = advent_of_code::read_file("examples", 11);
+        b.iter(|| count_occupied(create_grid(&input), Neighbourhood::moore(), 4));
+    }
+
+    #[bench]
+    fn bench_line_of_sight_scanning(b: &mut Bencher) {
+        let input = advent_of_code::read_file("examples", 11);
+        b.iter(|| {
+            let neighbourhood = Neighbourhood::line_of_sight(|s| *s == Space::Floor);
+            count_occupied(create_grid(&input), neighbourhood, 5)
+        });
+    }
+
+    #[bench]
+    fn bench_line_of_sight_cached(b: &mut Bencher) {
+        let input = advent_of_code::read_file("examples", 11);
+        b.iter(|| part_two(&input));
+    }
+
+    #[bench]
+    fn bench_dense_grid(b: &mut Bencher) {
+        let input = advent_of_code::read_file("examples", 11);
+        b.iter(|| count_occupied_dense(create_grid(&input), Neighbourhood::moore(), 4));
+    }
+
+    #[bench]
+    fn bench_dense_line_of_sight_scanning(b: &mut Bencher) {
+        let input = advent_of_code::read_file("examples", 11);
+        b.iter(|| {
+            let neighbourhood = Neighbourhood::line_of_sight(|s| *s == Space::Floor);
+            count_occupied_dense(create_grid(&input), neighbourhood, 5)
+        });
+    }
+
+    #[bench]
+    fn bench_dense_line_of_sight_cached(b: &mut Bencher) {
+        let input = advent_of_code::read_file("examples", 11);
+        b.iter(|| {
+            let grid = create_grid(&input);
+            let visible =
+                grid.visibility_graph(&Point2::directions_with_diagonals(), |s| *s == Space::Floor);
+            count_occupied_dense(grid, Neighbourhood::graph(visible), 5)
+        });
+    }
 }
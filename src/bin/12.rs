@@ -1,4 +1,4 @@
-use advent_of_code::algebra_helpers::{Point2, Point2Direction};
+use advent_of_code::algebra_helpers::{Point2, Point2Direction, Symmetry};
 use itertools::Itertools;
 use parse_display::{Display, FromStr};
 
@@ -36,7 +36,8 @@ impl Ferry {
         }
     }
 
-    pub fn adjust_by_instruction_part1(&mut self, instruction: &NavInstruction) {
+    // None for turns that aren't multiples of 90 degrees
+    pub fn adjust_by_instruction_part1(&mut self, instruction: &NavInstruction) -> Option<()> {
         match instruction {
             NavInstruction::North(d) => {
                 self.position = self
@@ -59,36 +60,14 @@ impl Ferry {
                     .get_point_in_direction(&Point2Direction::West, *d as isize);
             }
             NavInstruction::Left(d) => {
-                self.facing_direction = match d % 360 {
-                    0 => self.facing_direction.clone(),
-                    90 => self.facing_direction.direction_left(),
-                    180 => self.facing_direction.direction_left().direction_left(),
-                    270 => self
-                        .facing_direction
-                        .direction_left()
-                        .direction_left()
-                        .direction_left(),
-                    _ => {
-                        println!("{:?}", instruction);
-                        unimplemented!();
-                    }
-                };
+                self.facing_direction = self
+                    .facing_direction
+                    .turned_by_eighths(-quarter_turns(*d)? * 2);
             }
             NavInstruction::Right(d) => {
-                self.facing_direction = match d % 360 {
-                    0 => self.facing_direction.clone(),
-                    90 => self.facing_direction.direction_right(),
-                    180 => self.facing_direction.direction_right().direction_right(),
-                    270 => self
-                        .facing_direction
-                        .direction_right()
-                        .direction_right()
-                        .direction_right(),
-                    _ => {
-                        println!("{:?}", instruction);
-                        unimplemented!();
-                    }
-                };
+                self.facing_direction = self
+                    .facing_direction
+                    .turned_by_eighths(quarter_turns(*d)? * 2);
             }
             NavInstruction::Forward(d) => {
                 self.position = self
@@ -96,9 +75,10 @@ impl Ferry {
                     .get_point_in_direction(&self.facing_direction, *d as isize);
             }
         }
+        Some(())
     }
 
-    pub fn adjust_by_instruction_part2(&mut self, instruction: &NavInstruction) {
+    pub fn adjust_by_instruction_part2(&mut self, instruction: &NavInstruction) -> Option<()> {
         match instruction {
             NavInstruction::North(d) => {
                 self.waypoint = self
@@ -121,44 +101,26 @@ impl Ferry {
                     .get_point_in_direction(&Point2Direction::West, *d as isize);
             }
             NavInstruction::Left(d) => {
-                self.waypoint = match d % 360 {
-                    0 => self.waypoint,
-                    90 => spin_pos_left(&self.waypoint),
-                    180 => spin_pos_left(&spin_pos_left(&self.waypoint)),
-                    270 => spin_pos_right(&self.waypoint),
-                    _ => {
-                        println!("{:?}", instruction);
-                        unimplemented!();
-                    }
-                };
+                self.waypoint = Symmetry::rotation_clockwise(-(*d as isize))?.apply(self.waypoint);
             }
             NavInstruction::Right(d) => {
-                self.waypoint = match d % 360 {
-                    0 => self.waypoint,
-                    90 => spin_pos_right(&self.waypoint),
-                    180 => spin_pos_left(&spin_pos_left(&self.waypoint)),
-                    270 => spin_pos_left(&self.waypoint),
-                    _ => {
-                        println!("{:?}", instruction);
-                        unimplemented!();
-                    }
-                };
+                self.waypoint = Symmetry::rotation_clockwise(*d as isize)?.apply(self.waypoint);
             }
             NavInstruction::Forward(d) => {
                 for _ in 0..*d {
-                    self.position = self.position + self.waypoint;
+                    self.position += self.waypoint;
                 }
             }
         }
+        Some(())
     }
 }
 
-pub fn spin_pos_right(pos: &Point2<isize>) -> Point2<isize> {
-    Point2::new(-pos.0[1], pos.0[0])
-}
-
-pub fn spin_pos_left(pos: &Point2<isize>) -> Point2<isize> {
-    Point2::new(pos.0[1], -pos.0[0])
+// the puzzle only turns by multiples of 90 degrees
+fn quarter_turns(degrees: u32) -> Option<isize> {
+    degrees
+        .is_multiple_of(90)
+        .then_some((degrees / 90) as isize)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -169,7 +131,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     let mut ferry = Ferry::new();
 
     for i in instructions {
-        ferry.adjust_by_instruction_part1(&i);
+        ferry.adjust_by_instruction_part1(&i)?;
     }
 
     Some(ferry.position.length_manhattan() as u32)
@@ -183,7 +145,7 @@ pub fn part_two(input: &str) -> Option<u32> {
     let mut ferry = Ferry::new();
 
     for i in instructions {
-        ferry.adjust_by_instruction_part2(&i);
+        ferry.adjust_by_instruction_part2(&i)?;
     }
 
     Some(ferry.position.length_manhattan() as u32)
@@ -210,4 +172,17 @@ mod tests {
         let input = advent_of_code::read_file("examples", 12);
         assert_eq!(part_two(&input), Some(286));
     }
+
+    #[test]
+    fn test_ending_north_west() {
+        assert_eq!(part_one("W5\nN3\nF2\n"), Some(6));
+        assert_eq!(part_two("W15\nN3\nF2\n"), Some(18));
+    }
+
+    #[test]
+    fn test_invalid_turn() {
+        assert_eq!(part_one("F10\nR45\nF2\n"), None);
+        assert_eq!(part_two("F10\nL100\nF2\n"), None);
+        assert_eq!(part_two("F10\nL270\nF2\n"), Some(112));
+    }
 }
@@ -1,51 +1,152 @@
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code::helpers::bitset::Bitset;
 use itertools::Itertools;
 use parse_display::{Display, FromStr};
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 #[derive(Debug, PartialEq)]
 struct Bitmask {
-    ones_mask: u64,
-    zeros_mask: u64,
-    x_mask: u64,
+    ones_mask: Bitset<1>,
+    zeros_mask: Bitset<1>,
+    x_mask: Bitset<1>,
 }
 
 impl Bitmask {
     pub fn new() -> Self {
         Self {
-            ones_mask: 0,
-            zeros_mask: 0,
-            x_mask: 0,
+            ones_mask: Bitset::new(),
+            zeros_mask: Bitset::new(),
+            x_mask: Bitset::new(),
         }
     }
 
     pub fn apply_to_memory_value(&self, number: &u64) -> u64 {
-        (number & !(self.zeros_mask)) | self.ones_mask
+        ((Bitset::from(*number) & !self.zeros_mask) | self.ones_mask).into()
     }
 
-    pub fn apply_to_memory_address(&self, address: &u64) -> Vec<u64> {
-        let modified_address = address | self.ones_mask;
-        let combinations = (1 << self.x_mask.count_ones() as u64) as u64;
+    // the addresses a write in part two goes to, without listing them
+    pub fn apply_to_memory_address(&self, address: &u64) -> AddressPattern {
+        AddressPattern {
+            bits: (Bitset::from(*address) | self.ones_mask) & !self.x_mask,
+            floating: self.x_mask,
+        }
+    }
+}
 
-        let mut addresses = vec![];
-        for c in 0..combinations {
-            let mut current_combination_index = 0;
-            let mut working_zeros_mask: u64 = 0;
-            let mut working_ones_mask: u64 = 0;
-            for i in 0..64 {
-                if self.x_mask & (1 << i) > 0 {
-                    if c & (1 << current_combination_index) > 0 {
-                        working_ones_mask |= 1 << i;
-                    } else {
-                        working_zeros_mask |= 1 << i;
-                    }
-                    current_combination_index += 1;
-                }
-            }
+// All addresses that match `bits` outside of the floating bits, which can be
+// anything. Floating bits are 0 in `bits`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AddressPattern {
+    bits: Bitset<1>,
+    floating: Bitset<1>,
+}
 
-            addresses.push((modified_address & !working_zeros_mask) | working_ones_mask);
+impl AddressPattern {
+    // every address in the pattern, to check against
+    #[cfg(test)]
+    pub fn addresses(&self) -> impl Iterator<Item = u64> + '_ {
+        self.floating.subsets().map(|f| (self.bits | f).into())
+    }
+}
+
+const ADDRESS_BITS: usize = 36;
+
+// children for a 0 and a 1 at the next bit, or a value below the last bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+    Value(u64),
+    Branch(usize, usize),
+}
+
+// Memory of the decoder chip as a binary trie over the address bits, highest
+// bit first. Equal subtrees are stored only once, so a floating bit costs a
+// single update for both halves and the trie stays small no matter how many
+// addresses a write covers.
+#[derive(Debug)]
+struct FloatingMemory {
+    nodes: Vec<Node>,
+    ids: HashMap<Node, usize>,
+    root: usize,
+}
+
+impl FloatingMemory {
+    pub fn new() -> Self {
+        let mut memory = Self {
+            nodes: vec![],
+            ids: HashMap::new(),
+            root: 0,
+        };
+        memory.root = memory.intern(Node::Value(0));
+        for _ in 0..ADDRESS_BITS {
+            memory.root = memory.intern(Node::Branch(memory.root, memory.root));
         }
+        memory
+    }
 
-        addresses
+    fn intern(&mut self, node: Node) -> usize {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        self.nodes.push(node);
+        self.ids.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn write(&mut self, pattern: AddressPattern, value: u64) {
+        let mut done = HashMap::new();
+        self.root = self.write_below(self.root, ADDRESS_BITS, &pattern, value, &mut done);
+    }
+
+    // The subtree of id with the write applied, bit is the number of address
+    // bits left. `done` remembers subtrees already written to, which is what
+    // keeps floating bits cheap.
+    fn write_below(
+        &mut self,
+        id: usize,
+        bit: usize,
+        pattern: &AddressPattern,
+        value: u64,
+        done: &mut HashMap<usize, usize>,
+    ) -> usize {
+        if let Some(result) = done.get(&id) {
+            return *result;
+        }
+        let result = match self.nodes[id] {
+            Node::Value(_) => self.intern(Node::Value(value)),
+            Node::Branch(zero, one) => {
+                let i = bit - 1;
+                let floating = pattern.floating.contains(i);
+                let set = pattern.bits.contains(i);
+                let zero = if floating || !set {
+                    self.write_below(zero, i, pattern, value, done)
+                } else {
+                    zero
+                };
+                let one = if floating || set {
+                    self.write_below(one, i, pattern, value, done)
+                } else {
+                    one
+                };
+                self.intern(Node::Branch(zero, one))
+            }
+        };
+        done.insert(id, result);
+        result
+    }
+
+    // Sum of the values at every address. Up to 2^36 addresses with values
+    // up to 2^64 each, so it needs u128.
+    pub fn sum(&self) -> u128 {
+        // children always come before their parents
+        let mut sums = vec![0; self.nodes.len()];
+        for (id, node) in self.nodes.iter().enumerate() {
+            sums[id] = match node {
+                Node::Value(v) => *v as u128,
+                Node::Branch(zero, one) => sums[*zero] + sums[*one],
+            };
+        }
+        sums[self.root]
     }
 }
 
@@ -66,9 +167,9 @@ impl FromStr for Bitmask {
 
         for (idx, bit) in s.chars().rev().enumerate() {
             match bit {
-                '0' => bm.zeros_mask |= 1 << idx,
-                '1' => bm.ones_mask |= 1 << idx,
-                'X' => bm.x_mask |= 1 << idx,
+                '0' => bm.zeros_mask.insert(idx),
+                '1' => bm.ones_mask.insert(idx),
+                'X' => bm.x_mask.insert(idx),
                 _ => return Err(()),
             }
         }
@@ -105,26 +206,24 @@ pub fn part_one(input: &str) -> Option<u64> {
     Some(memory.values().sum())
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(input: &str) -> Option<u128> {
     let instructions = input
         .lines()
         .map(|l| l.parse::<Instruction>().unwrap())
         .collect_vec();
 
     let mut current_bm = Bitmask::new();
-    let mut memory: HashMap<u64, u64> = HashMap::new();
+    let mut memory = FloatingMemory::new();
     for i in instructions {
         match i {
             Instruction::MaskUpdate(bm) => current_bm = bm,
             Instruction::MemoryWrite(address, value) => {
-                for new_address in current_bm.apply_to_memory_address(&address) {
-                    memory.insert(new_address, value);
-                }
+                memory.write(current_bm.apply_to_memory_address(&address), value);
             }
         }
     }
 
-    Some(memory.values().sum())
+    Some(memory.sum())
 }
 
 fn main() {
@@ -135,7 +234,133 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    extern crate test;
+
     use super::*;
+    use advent_of_code::helpers::random::Random;
+    use test::Bencher;
+
+    // expands the X bits by counting through every combination, the bitset
+    // subset walk has to beat this
+    fn apply_to_memory_address_loop(mask: &Bitmask, address: &u64) -> Vec<u64> {
+        let x_mask = u64::from(mask.x_mask);
+        let modified_address = address | u64::from(mask.ones_mask);
+        let combinations = 1 << x_mask.count_ones();
+
+        let mut addresses = vec![];
+        for c in 0..combinations {
+            let mut current_combination_index = 0;
+            let mut working_zeros_mask: u64 = 0;
+            let mut working_ones_mask: u64 = 0;
+            for i in 0..64 {
+                if x_mask & (1 << i) > 0 {
+                    if c & (1 << current_combination_index) > 0 {
+                        working_ones_mask |= 1 << i;
+                    } else {
+                        working_zeros_mask |= 1 << i;
+                    }
+                    current_combination_index += 1;
+                }
+            }
+            addresses.push((modified_address & !working_zeros_mask) | working_ones_mask);
+        }
+        addresses
+    }
+
+    fn wide_mask() -> Bitmask {
+        "X1001X0XX10X0X1XX0X0100X01X0XX0X0X01".parse().unwrap()
+    }
+
+    #[test]
+    fn test_addresses() {
+        let mask = wide_mask();
+        let x_mask = u64::from(mask.x_mask);
+        let fixed = (12345 | u64::from(mask.ones_mask)) & !x_mask;
+        let addresses = mask
+            .apply_to_memory_address(&12345)
+            .addresses()
+            .collect_vec();
+        // every way to fill in the floating bits, each once
+        assert_eq!(addresses.len(), 1 << 15);
+        assert!(addresses.iter().all(|a| a & !x_mask == fixed));
+        assert!(addresses.iter().all_unique());
+        assert_eq!(
+            mask.to_string(),
+            format!(
+                "Z{:064b} O{:064b} X{:064b}",
+                u64::from(mask.zeros_mask),
+                u64::from(mask.ones_mask),
+                u64::from(mask.x_mask)
+            )
+        );
+    }
+
+    #[bench]
+    fn bench_addresses_loop(b: &mut Bencher) {
+        let mask = wide_mask();
+        b.iter(|| apply_to_memory_address_loop(&mask, &12345).len());
+    }
+
+    #[bench]
+    fn bench_addresses_bitset(b: &mut Bencher) {
+        let mask = wide_mask();
+        b.iter(|| mask.apply_to_memory_address(&12345).addresses().count());
+    }
+
+    fn random_mask(random: &mut Random, floating_chance: u64) -> Bitmask {
+        (0..36)
+            .map(|_| match random.below(100) {
+                r if r < floating_chance => 'X',
+                r if r % 2 == 0 => '0',
+                _ => '1',
+            })
+            .collect::<String>()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_same_as_expanded_memory() {
+        let mut random = Random::new(42);
+        let mut memory = FloatingMemory::new();
+        let mut expanded = HashMap::new();
+        for _ in 0..200 {
+            let mask = random_mask(&mut random, 25);
+            let pattern = mask.apply_to_memory_address(&random.below(1 << 36));
+            let value = random.below(1000);
+            memory.write(pattern, value);
+            for address in pattern.addresses() {
+                expanded.insert(address, value);
+            }
+        }
+        assert_eq!(memory.sum(), expanded.values().sum::<u64>() as u128);
+    }
+
+    #[test]
+    fn test_wide_masks() {
+        // 2^30 and more addresses per write, far too many to store
+        let mut random = Random::new(1);
+        let mut memory = FloatingMemory::new();
+        for i in 0..500 {
+            let mask = random_mask(&mut random, 90);
+            memory.write(mask.apply_to_memory_address(&i), 1);
+        }
+        assert!(memory.sum() > 1 << 35);
+        assert!(memory.sum() <= 1 << 36);
+
+        let everything = AddressPattern {
+            bits: Bitset::new(),
+            floating: Bitset::from((1 << 36) - 1),
+        };
+        memory.write(everything, 3);
+        assert_eq!(memory.sum(), 3 << 36);
+        memory.write(wide_mask().apply_to_memory_address(&0), 4);
+        assert_eq!(memory.sum(), (3 << 36) + (1 << 15));
+
+        // far beyond u64
+        memory.write(everything, u64::MAX);
+        assert_eq!(memory.sum(), (u64::MAX as u128) << 36);
+    }
 
     #[test]
     fn test_part_one() {
@@ -1,63 +1,21 @@
-use std::collections::HashMap;
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code::helpers::memory_game::MemoryGame;
 use itertools::Itertools;
 
-pub fn find_next_said_number(
-    last_said_number: &u64,
-    current_offset: &u64,
-    mut hm: HashMap<u64, u64>,
-) -> (u64, HashMap<u64, u64>) {
-    if let Some(x) = hm.get(last_said_number) {
-        let offset = *x;
-        hm.insert(*last_said_number, *current_offset);
-        return (*current_offset - offset, hm);
-    }
-
-    hm.insert(*last_said_number, *current_offset);
-    (0, hm)
-}
-
-pub fn part_one(input: &str) -> Option<u64> {
-    let numbers = input
+fn parse(input: &str) -> Vec<u32> {
+    input
         .lines()
-        .flat_map(|s| s.split(',').filter_map(|n| n.parse::<u64>().ok()))
-        .collect_vec();
-
-    let mut hm: HashMap<u64, u64> = HashMap::new();
-    let mut said_number = 0;
-    for (idx, n) in numbers.iter().enumerate() {
-        (said_number, hm) = find_next_said_number(n, &(idx as u64), hm);
-    }
-
-    for i in numbers.len()..2019 {
-        (said_number, hm) = find_next_said_number(&said_number, &(i as u64), hm);
-    }
-
-    Some(said_number)
+        .flat_map(|s| s.split(',').filter_map(|n| n.parse::<u32>().ok()))
+        .collect_vec()
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let numbers = input
-        .lines()
-        .flat_map(|s| s.split(',').filter_map(|n| n.parse::<u64>().ok()))
-        .collect_vec();
-
-    let mut hm: HashMap<u64, u64> = HashMap::new();
-    let mut said_number = 0;
-    for (idx, n) in numbers.iter().enumerate() {
-        (said_number, hm) = find_next_said_number(n, &(idx as u64), hm);
-    }
-
-    let mut log_number = 10;
-    for i in numbers.len()..(30000000 - 1) {
-        (said_number, hm) = find_next_said_number(&said_number, &(i as u64), hm);
-        if i >= log_number {
-            // println!("At {} with number {}", i, said_number);
-            log_number *= 10;
-        }
-    }
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(MemoryGame::new(&parse(input)).number_at(2020))
+}
 
-    Some(said_number)
+pub fn part_two(input: &str) -> Option<u32> {
+    Some(MemoryGame::new(&parse(input)).number_at(30_000_000))
 }
 
 fn main() {
@@ -68,7 +26,10 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    extern crate test;
+
     use super::*;
+    use test::Bencher;
 
     #[test]
     fn test_part_one() {
@@ -81,4 +42,9 @@ mod tests {
         let input = advent_of_code::read_file("examples", 15);
         assert_eq!(part_two(&input), Some(175594));
     }
+
+    #[bench]
+    fn bench_memory_game(b: &mut Bencher) {
+        b.iter(|| MemoryGame::new(&[0, 3, 6]).number_at(100_000));
+    }
 }
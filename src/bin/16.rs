@@ -1,34 +1,25 @@
-use std::{collections::HashMap, ops::Range};
+use std::ops::Range;
 
-use itertools::Itertools;
-
-#[derive(Debug)]
-pub struct RuleRange(Range<u32>);
-
-impl From<(u32, u32)> for RuleRange {
-    fn from(value: (u32, u32)) -> Self {
-        Self(value.0..(value.1 + 1))
-    }
-}
+use advent_of_code::helpers::{assignment::solve_assignment, intervals::IntervalSet};
 
 #[derive(Debug)]
 pub struct Rule {
     name: String,
-    ranges: Vec<RuleRange>,
+    ranges: IntervalSet<u32>,
 }
 
-impl From<(&str, Vec<RuleRange>)> for Rule {
-    fn from(value: (&str, Vec<RuleRange>)) -> Self {
+impl From<(&str, Vec<Range<u32>>)> for Rule {
+    fn from(value: (&str, Vec<Range<u32>>)) -> Self {
         Self {
             name: value.0.to_string(),
-            ranges: value.1,
+            ranges: value.1.into_iter().collect(),
         }
     }
 }
 
 impl Rule {
     pub fn is_rule_valid_for_number(&self, number: u32) -> bool {
-        self.ranges.iter().any(|range| range.0.contains(&number))
+        self.ranges.contains(&number)
     }
 
     pub fn is_rule_possible_for_numbers(&self, numbers: &[u32]) -> bool {
@@ -36,6 +27,12 @@ impl Rule {
     }
 }
 
+pub fn union_of_rules(rules: &[Rule]) -> IntervalSet<u32> {
+    rules
+        .iter()
+        .fold(IntervalSet::new(), |acc, rule| acc.union(&rule.ranges))
+}
+
 #[derive(Debug)]
 pub struct Ticket {
     raw_numbers: Vec<u32>,
@@ -48,14 +45,15 @@ impl From<Vec<u32>> for Ticket {
 }
 
 impl Ticket {
-    pub fn are_all_fields_valid_for_every_rule(&self, rules: &[Rule]) -> Result<(), u32> {
-        for n in &self.raw_numbers {
-            if rules.iter().any(|rule| rule.is_rule_valid_for_number(*n)) {
-                continue;
-            }
-            return Err(*n);
+    // `valid_numbers` is the union of all rules, see `union_of_rules`
+    pub fn are_all_fields_valid_for_any_rule(
+        &self,
+        valid_numbers: &IntervalSet<u32>,
+    ) -> Result<(), u32> {
+        match self.raw_numbers.iter().find(|n| !valid_numbers.contains(n)) {
+            Some(n) => Err(*n),
+            None => Ok(()),
         }
-        Ok(())
     }
 }
 
@@ -80,14 +78,16 @@ mod parser {
     use nom::{
         bytes::complete::{tag, take_till},
         character::complete::{line_ending, u32},
-        combinator::into,
+        combinator::{into, map_opt},
         error::Error as NomError,
         multi::separated_list1,
         sequence::{pair, preceded, separated_pair, terminated, tuple},
         Finish, IResult,
     };
 
-    use crate::{ReferenceFile, Rule, RuleRange, Ticket};
+    use std::ops::Range;
+
+    use crate::{ReferenceFile, Rule, Ticket};
 
     pub fn parse_file(s: &str) -> Result<ReferenceFile, NomError<&str>> {
         let (_, x) = into(parse_file_raw)(s).finish()?;
@@ -109,7 +109,7 @@ mod parser {
         into(parse_rule_raw)(s)
     }
 
-    fn parse_rule_raw(s: &str) -> IResult<&str, (&str, Vec<RuleRange>)> {
+    fn parse_rule_raw(s: &str) -> IResult<&str, (&str, Vec<Range<u32>>)> {
         separated_pair(parse_rule_name, tag(": "), parse_rangelist)(s)
     }
 
@@ -117,16 +117,16 @@ mod parser {
         take_till(|c| c == ':')(s)
     }
 
-    fn parse_rangelist(s: &str) -> IResult<&str, Vec<RuleRange>> {
+    fn parse_rangelist(s: &str) -> IResult<&str, Vec<Range<u32>>> {
         separated_list1(tag(" or "), parse_range)(s)
     }
 
-    fn parse_range(s: &str) -> IResult<&str, RuleRange> {
-        into(parse_range_raw)(s)
-    }
-
-    fn parse_range_raw(s: &str) -> IResult<&str, (u32, u32)> {
-        separated_pair(u32, tag("-"), u32)(s)
+    // ranges in the input are inclusive, one ending at u32::MAX can't be
+    // stored and fails to parse
+    fn parse_range(s: &str) -> IResult<&str, Range<u32>> {
+        map_opt(separated_pair(u32, tag("-"), u32), |(from, to)| {
+            Some(from..to.checked_add(1)?)
+        })(s)
     }
 
     fn parse_my_ticket(s: &str) -> IResult<&str, Ticket> {
@@ -169,9 +169,11 @@ pub fn construct_field_lists(tickets: &[Ticket], amount_fields: usize) -> Vec<Ve
 pub fn part_one(input: &str) -> Option<u32> {
     let ref_file = parser::parse_file(input).unwrap();
 
+    let valid_numbers = union_of_rules(&ref_file.rules);
+
     let mut sum = 0;
     for ticket in ref_file.other_tickets {
-        if let Err(error_code) = ticket.are_all_fields_valid_for_every_rule(&ref_file.rules) {
+        if let Err(error_code) = ticket.are_all_fields_valid_for_any_rule(&valid_numbers) {
             sum += error_code;
         }
     }
@@ -182,50 +184,36 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u64> {
     let ref_file = parser::parse_file(input).unwrap();
 
+    let valid_numbers = union_of_rules(&ref_file.rules);
+
     let mut valid_tickets = vec![];
     for ticket in ref_file.other_tickets {
-        if ticket.are_all_fields_valid_for_every_rule(&ref_file.rules) == Ok(()) {
+        if ticket.are_all_fields_valid_for_any_rule(&valid_numbers) == Ok(()) {
             valid_tickets.push(ticket);
         }
     }
     let fieldlists = construct_field_lists(&valid_tickets, ref_file.rules.len());
 
-    let mut recognized_fields: HashMap<String, usize> = HashMap::new();
-    let mut last_amount_of_recognized_fields = 100;
-    while recognized_fields.len() < fieldlists.len()
-        && last_amount_of_recognized_fields != recognized_fields.len()
-    {
-        last_amount_of_recognized_fields = recognized_fields.len();
-        for rule in &ref_file.rules {
-            let mut possible_ids = vec![];
-            for (flid, fieldlist) in fieldlists.iter().enumerate() {
-                if recognized_fields.values().contains(&flid) {
-                    continue;
-                }
-                if rule.is_rule_possible_for_numbers(fieldlist) {
-                    possible_ids.push(flid);
-                }
-            }
-
-            if possible_ids.len() == 1 {
-                recognized_fields.insert(rule.name.clone(), *possible_ids.first().unwrap());
+    let candidates = ref_file.rules.iter().map(|rule| {
+        let possible_ids = fieldlists
+            .iter()
+            .enumerate()
+            .filter(|(_, fieldlist)| rule.is_rule_possible_for_numbers(fieldlist))
+            .map(|(flid, _)| flid)
+            .collect::<Vec<_>>();
+        (rule.name.as_str(), possible_ids)
+    });
+    let recognized_fields = solve_assignment(candidates).ok()?;
+
+    let mut result = 1;
+    for (rule_name, id) in recognized_fields {
+        if rule_name.starts_with("departure") {
+            if let Some(x) = ref_file.my_ticket.raw_numbers.get(id) {
+                result *= *x as u64;
             }
         }
     }
-
-    if recognized_fields.len() == ref_file.rules.len() {
-        let mut result = 1;
-        for (rule_name, id) in recognized_fields {
-            if rule_name.starts_with("departure") {
-                if let Some(x) = ref_file.my_ticket.raw_numbers.get(id) {
-                    result *= *x as u64;
-                }
-            }
-        }
-        return Some(result);
-    }
-
-    None
+    Some(result)
 }
 
 fn main() {
@@ -249,4 +237,13 @@ mod tests {
         let input = advent_of_code::read_file("examples", 16);
         assert_eq!(part_two(&input), Some(7));
     }
+
+    #[test]
+    fn test_range_overflow() {
+        let input = "a: 1-3 or 5-4294967295\n\nyour ticket:\n1\n\nnearby tickets:\n2";
+        assert!(parser::parse_file(input).is_err());
+        let input = input.replace("4294967295", "4294967294");
+        let rules = parser::parse_file(&input).unwrap().rules;
+        assert!(union_of_rules(&rules).contains(&4294967294));
+    }
 }
@@ -1,7 +1,9 @@
-use advent_of_code::algebra_helpers::{Point, PointGrid, PointGridIterator};
-use parse_display::{Display, FromStr, ParseError};
+use advent_of_code::algebra_helpers::{
+    Automaton, GridParseError, Growth, Neighbourhood, Point, PointGrid,
+};
+use parse_display::{Display, FromStr};
 
-#[derive(Display, FromStr, PartialEq, Debug)]
+#[derive(Display, FromStr, PartialEq, Debug, Clone)]
 enum Cell {
     #[display("#")]
     Active,
@@ -28,78 +30,44 @@ impl Cell {
 struct PocketDimension<const N: usize>(PointGrid<isize, N, Cell>);
 
 impl<const N: usize> PocketDimension<N> {
-    pub fn calculate_next_gol_step(&self) -> PocketDimension<N> {
-        let mut new_dimension: PocketDimension<N> = Self(PointGrid::default());
-        let (min, max) = self.0.dimensions();
-        for p in PointGridIterator::new(min + Point::filled(-1), max + Point::filled(2)) {
-            let old_state = self.0.get(&p).unwrap_or(&Cell::Inactive);
-            let amount_active_neighbors = self.amount_active_neighbors(&p) as isize;
-            let new_state = old_state.calculate_status_from_neighbor_count(amount_active_neighbors);
-            // println!(
-            //     "Point {} has {} neighbors: {} -> {}",
-            //     p, amount_active_neighbors, old_state, new_state
-            // );
-
-            if new_state == Cell::Active {
-                new_dimension.0.insert(p, new_state);
-            }
-        }
-
-        new_dimension
-    }
-
-    pub fn amount_active_neighbors(&self, pos: &Point<isize, N>) -> usize {
-        Point::directions_with_diagonals()
-            .iter()
-            .filter_map(|d| self.0.get(&(*pos + *d)))
-            .filter(|c| **c == Cell::Active)
-            .count()
-    }
-
-    pub fn amount_active_cells(&self) -> usize {
-        self.0 .0.values().filter(|v| **v == Cell::Active).count()
+    // runs the given amount of cycles and returns the number of active cells
+    pub fn amount_active_cells_after(self, cycles: usize) -> usize {
+        let mut automaton = Automaton::new(
+            self.0,
+            Neighbourhood::moore(),
+            Growth::Unbounded {
+                background: Cell::Inactive,
+            },
+            |cell: &Cell, neighbours: &[&Cell]| {
+                let amount_active_neighbors =
+                    neighbours.iter().filter(|c| ***c == Cell::Active).count();
+                cell.calculate_status_from_neighbor_count(amount_active_neighbors as isize)
+            },
+        );
+        automaton.run(cycles);
+        automaton.grid().len()
     }
 }
 
 impl<const N: usize> TryFrom<&str> for PocketDimension<N> {
-    type Error = ParseError;
+    type Error = GridParseError;
 
+    // only active cells are stored
     fn try_from(input: &str) -> Result<Self, Self::Error> {
-        let mut dimension: PocketDimension<N> = Self(PointGrid::default());
-
-        for (y, row) in input.lines().enumerate() {
-            for (x, character) in row.chars().enumerate() {
-                let mut point: [isize; N] = Point::zero().into();
-                point[0] = x as isize;
-                point[1] = y as isize;
-                dimension
-                    .0
-                    .insert(point.into(), character.to_string().parse::<Cell>()?);
-            }
-        }
-
-        Ok(dimension)
+        Ok(Self(PointGrid::parse_slice(input, Point::zero(), &['.'])?))
     }
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let mut pd: PocketDimension<3> = input.try_into().unwrap();
+    let pd: PocketDimension<3> = input.try_into().unwrap();
 
-    for _ in 0..6 {
-        pd = pd.calculate_next_gol_step();
-    }
-
-    Some(pd.amount_active_cells())
+    Some(pd.amount_active_cells_after(6))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let mut pd: PocketDimension<4> = input.try_into().unwrap();
-
-    for _ in 0..6 {
-        pd = pd.calculate_next_gol_step();
-    }
+    let pd: PocketDimension<4> = input.try_into().unwrap();
 
-    Some(pd.amount_active_cells())
+    Some(pd.amount_active_cells_after(6))
 }
 
 fn main() {
@@ -3,6 +3,10 @@
 // parser -> shunting yard algorithm in ASTNode
 // evaluation (instead of code generation) -> evaluate() in ASTNode
 
+use advent_of_code::helpers::bigint::BigUint;
+use advent_of_code::helpers::dot::Dot;
+use num_traits::Zero;
+
 mod tokenizer {
     use nom::{
         branch::alt,
@@ -82,9 +86,10 @@ enum ASTNode {
 
 // for all parts
 impl ASTNode {
-    pub fn evaluate(&self) -> usize {
+    // evaluates with arbitrary precision, products of long lines overflow usize
+    pub fn evaluate(&self) -> BigUint {
         match self {
-            Self::Leaf(n) => *n,
+            Self::Leaf(n) => BigUint::from(*n),
             Self::Addition(lhs, rhs) => lhs.evaluate() + rhs.evaluate(),
             Self::Multiplication(lhs, rhs) => lhs.evaluate() * rhs.evaluate(),
         }
@@ -159,8 +164,44 @@ impl ASTNode {
     }
 }
 
-pub fn part_one(_input: &str) -> Option<usize> {
-    let mut sum = 0;
+impl ASTNode {
+    // adds the subtree with ids counting up from `next_id`, returns the id of
+    // this node
+    fn add_to_dot(&self, dot: &mut Dot, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let (label, children) = match self {
+            Self::Leaf(n) => (n.to_string(), vec![]),
+            Self::Addition(lhs, rhs) => (String::from("+"), vec![lhs, rhs]),
+            Self::Multiplication(lhs, rhs) => (String::from("*"), vec![lhs, rhs]),
+        };
+        dot.node(id, label);
+        for child in children {
+            let child_id = child.add_to_dot(dot, next_id);
+            dot.edge(id, child_id);
+        }
+        id
+    }
+}
+
+// the expression trees of every line with the precedence of part two, each
+// below a box with its line number
+pub fn to_dot(input: &str) -> Dot {
+    let mut dot = Dot::digraph("expressions");
+    let mut next_id = 0;
+    for (i, l) in input.lines().enumerate() {
+        let tokens = tokenizer::parse_tokens(l).unwrap();
+        let ast = ASTNode::shunting_yard_algorithm(tokens, false);
+        let line = format!("line {}", i + 1);
+        dot.node_with(&line, &[("shape", "box")]);
+        let root = ast.add_to_dot(&mut dot, &mut next_id);
+        dot.edge(&line, root);
+    }
+    dot
+}
+
+pub fn part_one(_input: &str) -> Option<BigUint> {
+    let mut sum = BigUint::zero();
     for l in _input.lines() {
         let tokens = tokenizer::parse_tokens(l).unwrap();
         let ast = ASTNode::shunting_yard_algorithm(tokens, true);
@@ -170,8 +211,8 @@ pub fn part_one(_input: &str) -> Option<usize> {
     Some(sum)
 }
 
-pub fn part_two(_input: &str) -> Option<usize> {
-    let mut sum = 0;
+pub fn part_two(_input: &str) -> Option<BigUint> {
+    let mut sum = BigUint::zero();
     for l in _input.lines() {
         let tokens = tokenizer::parse_tokens(l).unwrap();
         let ast = ASTNode::shunting_yard_algorithm(tokens, false);
@@ -185,21 +226,55 @@ fn main() {
     let input = &advent_of_code::read_file("inputs", 18);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::solve!(dot, 18, to_dot, input);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_evaluate_without_overflow() {
+        let line = ["9999999999"; 4].join(" * ");
+        assert_eq!(
+            part_one(&line).map(|n| n.to_string()),
+            Some(String::from("9999999996000000000599999999960000000001"))
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        // multiplication binds weakest, so it ends up at the root
+        let dot = to_dot("1 + 2 * 3").to_string();
+        assert_eq!(
+            dot,
+            "\
+digraph \"expressions\" {
+    \"line 1\" [shape=\"box\"];
+    \"0\" [label=\"*\"];
+    \"1\" [label=\"+\"];
+    \"2\" [label=\"1\"];
+    \"1\" -> \"2\";
+    \"3\" [label=\"2\"];
+    \"1\" -> \"3\";
+    \"0\" -> \"1\";
+    \"4\" [label=\"3\"];
+    \"0\" -> \"4\";
+    \"line 1\" -> \"0\";
+}
+"
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 18);
-        assert_eq!(part_one(&input), Some(26335));
+        assert_eq!(part_one(&input), Some(BigUint::from(26335u32)));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 18);
-        assert_eq!(part_two(&input), Some(693891));
+        assert_eq!(part_two(&input), Some(BigUint::from(693891u32)));
     }
 }
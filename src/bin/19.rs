@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use advent_of_code::helpers::dot::Dot;
+
 // So for part 2 we need to explore all possible choices and thus we need to
 // split at each choice. In this particular case we keep a list of all splits
 // with all unmatched strings per split. If we are done parsing and this list
@@ -38,7 +40,7 @@ impl RefList {
                 return_remaining_options.push(ro);
             }
         }
-        return return_remaining_options;
+        return_remaining_options
     }
 }
 
@@ -84,23 +86,64 @@ impl Rule {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct RuleSet(HashMap<u16, Rule>);
 
-impl Default for RuleSet {
-    fn default() -> Self {
-        Self(HashMap::default())
-    }
-}
-
 impl RuleSet {
     fn match_str(&self, s: &str) -> bool {
         let initial_rule = self.0.get(&0).unwrap();
-        let remaining = initial_rule.match_str(&self, s);
+        let remaining = initial_rule.match_str(self, s);
         remaining.iter().any(|s| s.is_empty())
     }
 }
 
+impl RefList {
+    // edges to the referenced rules, numbered when the order matters
+    fn add_to_dot(&self, dot: &mut Dot, from: &str) {
+        for (i, r) in self.0.iter().enumerate() {
+            if self.0.len() > 1 {
+                dot.labelled_edge(from, r, i + 1);
+            } else {
+                dot.edge(from, r);
+            }
+        }
+    }
+}
+
+impl RuleSet {
+    // Literals are boxes, choices diamonds with one point per alternative.
+    fn to_dot(&self) -> Dot {
+        let mut dot = Dot::digraph("rules");
+        let mut keys = self.0.keys().collect::<Vec<_>>();
+        keys.sort();
+        for k in keys {
+            let id = k.to_string();
+            match &self.0[k] {
+                Rule::Literal(c) => {
+                    dot.node_with(
+                        &id,
+                        &[("label", &format!("{}: {}", k, c)), ("shape", "box")],
+                    );
+                }
+                Rule::Concatenation(reflist) => {
+                    dot.node(&id, &id);
+                    reflist.add_to_dot(&mut dot, &id);
+                }
+                Rule::Choice(first, second) => {
+                    dot.node_with(&id, &[("label", &id), ("shape", "diamond")]);
+                    for (i, reflist) in [first, second].into_iter().enumerate() {
+                        let alternative = format!("{}/{}", k, i + 1);
+                        dot.node_with(&alternative, &[("label", ""), ("shape", "point")]);
+                        dot.edge(&id, &alternative);
+                        reflist.add_to_dot(&mut dot, &alternative);
+                    }
+                }
+            }
+        }
+        dot
+    }
+}
+
 mod parser {
     use nom::{
         branch::alt,
@@ -162,10 +205,14 @@ fn parse_input(s: &str) -> (RuleSet, Vec<&str>) {
     (ruleset, messages_str.lines().collect())
 }
 
+pub fn to_dot(input: &str) -> Dot {
+    parse_input(input).0.to_dot()
+}
+
 pub fn part_one(_input: &str) -> Option<u32> {
     let (ruleset, messages) = parse_input(_input);
 
-    Some(messages.iter().filter(|s| ruleset.match_str(*s)).count() as u32)
+    Some(messages.iter().filter(|s| ruleset.match_str(s)).count() as u32)
 }
 
 pub fn part_two(_input: &str) -> Option<u32> {
@@ -179,13 +226,14 @@ pub fn part_two(_input: &str) -> Option<u32> {
         Rule::Choice(RefList(vec![42, 31]), RefList(vec![42, 11, 31])),
     );
 
-    Some(messages.iter().filter(|s| ruleset.match_str(*s)).count() as u32)
+    Some(messages.iter().filter(|s| ruleset.match_str(s)).count() as u32)
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 19);
     advent_of_code::solve!(1, part_one, input);
     advent_of_code::solve!(2, part_two, input);
+    advent_of_code::solve!(dot, 19, to_dot, input);
 }
 
 #[cfg(test)]
@@ -202,9 +250,19 @@ mod tests {
         ruleset.0.insert(2, Rule::Literal('b'));
         ruleset.0.insert(3, Rule::Literal('c'));
 
-        assert_eq!(ruleset.match_str("abc"), true);
-        assert_eq!(ruleset.match_str("abb"), false);
-        assert_eq!(ruleset.match_str("cbc"), false);
+        assert!(ruleset.match_str("abc"));
+        assert!(!ruleset.match_str("abb"));
+        assert!(!ruleset.match_str("cbc"));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = to_dot(&advent_of_code::read_file("examples", 19)).to_string();
+        assert!(dot.contains("\"1\" [label=\"1: a\", shape=\"box\"];"));
+        assert!(dot.contains("\"42\" [label=\"42\", shape=\"diamond\"];"));
+        assert!(dot.contains("\"42\" -> \"42/2\";"));
+        assert!(dot.contains("\"42/2\" -> \"1\" [label=\"2\"];"));
+        assert!(dot.contains("\"11\" -> \"31\" [label=\"2\"];"));
     }
 
     #[test]
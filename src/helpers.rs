@@ -1,3 +1,22 @@
+pub mod assignment;
+pub mod bigint;
+pub mod bitset;
+pub mod console;
+pub mod cycles;
+pub mod dot;
+pub mod dsu;
+pub mod graph;
+pub mod intervals;
+pub mod ksum;
+pub mod memory_game;
+pub mod modular;
+pub mod random;
+pub mod rational;
+
+use num_traits::Signed;
+
+use crate::algebra_helpers::Scalar;
+
 pub fn lcm(first: usize, second: usize) -> usize {
     first * second / gcd(first, second)
 }
@@ -21,29 +40,29 @@ pub fn gcd(first: usize, second: usize) -> usize {
 }
 
 // returns (g, x, y) for a*x + b*y = g
-pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+pub fn egcd<T: Scalar + Signed>(a: T, b: T) -> (T, T, T) {
     let mut max = a;
     let mut min = b;
 
-    let mut prev_x: i64 = 1;
-    let mut x: i64 = 0;
-    let mut prev_y: i64 = 0;
-    let mut y: i64 = 1;
+    let mut prev_x = T::one();
+    let mut x = T::zero();
+    let mut prev_y = T::zero();
+    let mut y = T::one();
 
     loop {
-        let q = max / min;
-        (x, prev_x) = (prev_x - q * x, x);
-        (y, prev_y) = (prev_y - q * y, y);
-        (max, min) = (min, max % min);
+        let q = max.clone() / min.clone();
+        (x, prev_x) = (prev_x - q.clone() * x.clone(), x);
+        (y, prev_y) = (prev_y - q * y.clone(), y);
+        (max, min) = (min.clone(), max % min);
 
-        if min == 0 {
+        if min.is_zero() {
             return (max, prev_x, prev_y);
         }
     }
 }
 
 // Extended gcd for multiple numbers
-pub fn egcd_mn(numbers: &Vec<i64>) -> Option<(i64, Vec<i64>)> {
+pub fn egcd_mn(numbers: &[i64]) -> Option<(i64, Vec<i64>)> {
     if numbers.len() < 2 {
         return None;
     }
@@ -61,22 +80,30 @@ pub fn egcd_mn(numbers: &Vec<i64>) -> Option<(i64, Vec<i64>)> {
 
 // Chinese Remainder Theorem
 // https://en.wikipedia.org/wiki/Chinese_remainder_theorem#Existence_.28direct_construction.29
-pub fn crt(numbers_with_remainders: Vec<(i64, i64)>) -> i64 {
-    let full_product: i64 = numbers_with_remainders.iter().map(|n| n.0).product();
-    let mut result = 0;
-
-    for (n, offset) in numbers_with_remainders.iter() {
-        let product_without_n = full_product / n;
-        let (_, inv, _) = egcd(product_without_n, *n);
+pub fn crt<T: Scalar + Signed>(numbers_with_remainders: Vec<(T, T)>) -> T {
+    let full_product = numbers_with_remainders
+        .iter()
+        .fold(T::one(), |product, (n, _)| product * n.clone());
+    let mut result = T::zero();
+
+    for (n, offset) in numbers_with_remainders {
+        let product_without_n = full_product.clone() / n.clone();
+        let (_, inv, _) = egcd(product_without_n.clone(), n);
         result += offset * product_without_n * inv;
     }
 
-    result.rem_euclid(full_product)
+    let result = result % full_product.clone();
+    if result.is_negative() {
+        result + full_product
+    } else {
+        result
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bigint::BigInt;
 
     #[test]
     fn test_gcd() {
@@ -92,7 +119,33 @@ mod tests {
 
     #[test]
     fn test_egcd_mn() {
-        assert_eq!(egcd_mn(&vec![20, 15, 10]), Some((5, vec![1, -1, 0])));
-        assert_eq!(egcd_mn(&vec![19, 31, 59]), Some((1, vec![-13, 8, 0])));
+        assert_eq!(egcd_mn(&[20, 15, 10]), Some((5, vec![1, -1, 0])));
+        assert_eq!(egcd_mn(&[19, 31, 59]), Some((1, vec![-13, 8, 0])));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(vec![(3, 2), (5, 3), (7, 2)]), 23);
+        assert_eq!(crt(vec![(17, 0), (13, 11), (19, 16)]), 3417);
+    }
+
+    #[test]
+    fn test_crt_big() {
+        // the product of these moduli is far beyond i128
+        let numbers = [
+            "1000000000000000000000000000057",
+            "1000000000000000000000000000099",
+            "1000000000000000000000000000111",
+        ]
+        .map(|n| n.parse::<BigInt>().unwrap());
+        let x = "123456789012345678901234567890123456789012345678901234567890"
+            .parse::<BigInt>()
+            .unwrap();
+        let result = crt(numbers.iter().map(|n| (n.clone(), &x % n)).collect());
+        assert_eq!(result, x);
+        assert_eq!(
+            egcd(BigInt::from(240), BigInt::from(46)),
+            (BigInt::from(2), BigInt::from(-9), BigInt::from(47))
+        );
     }
 }
@@ -0,0 +1,322 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::mem;
+
+use super::cycles::fixed_point;
+
+// Solver for "which X belongs to which Y" puzzles (ticket fields, allergens):
+// given the candidate values for every key, find the assignment where each
+// key gets exactly one distinct value.
+//
+// Unit propagation (a key with a single candidate, or a value that only one
+// key can take) solves the typical puzzle on its own. Whatever is left is
+// handed to Hopcroft-Karp for a bipartite matching, which is then checked for
+// uniqueness.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssignmentError {
+    NoSolution,
+    MultipleSolutions,
+}
+
+impl fmt::Display for AssignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssignmentError::NoSolution => write!(f, "no valid assignment exists"),
+            AssignmentError::MultipleSolutions => write!(f, "assignment is not unique"),
+        }
+    }
+}
+
+const UNMATCHED: usize = usize::MAX;
+
+pub fn solve_assignment<K, V, I>(
+    candidates: impl IntoIterator<Item = (K, I)>,
+) -> Result<HashMap<K, V>, AssignmentError>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    I: IntoIterator<Item = V>,
+{
+    // work on indices internally
+    let mut keys: Vec<K> = vec![];
+    let mut values: Vec<V> = vec![];
+    let mut value_ids: HashMap<V, usize> = HashMap::new();
+    let mut options: Vec<HashSet<usize>> = vec![];
+    for (k, vs) in candidates {
+        keys.push(k);
+        options.push(
+            vs.into_iter()
+                .map(|v| {
+                    *value_ids.entry(v.clone()).or_insert_with(|| {
+                        values.push(v);
+                        values.len() - 1
+                    })
+                })
+                .collect(),
+        );
+    }
+
+    let mut assigned = propagate(&mut options)?;
+
+    let open_keys = (0..keys.len())
+        .filter(|k| assigned[*k] == UNMATCHED)
+        .collect::<Vec<_>>();
+    if !open_keys.is_empty() {
+        let adjacency = open_keys
+            .iter()
+            .map(|k| options[*k].iter().copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut matching = BipartiteMatching::new(adjacency, values.len());
+        if matching.hopcroft_karp() < open_keys.len() {
+            return Err(AssignmentError::NoSolution);
+        }
+        if !matching.is_unique() {
+            return Err(AssignmentError::MultipleSolutions);
+        }
+        for (i, k) in open_keys.iter().enumerate() {
+            assigned[*k] = matching.match_left[i];
+        }
+    }
+
+    Ok(keys
+        .into_iter()
+        .zip(assigned)
+        .map(|(k, v)| (k, values[v].clone()))
+        .collect())
+}
+
+// Repeatedly assigns keys with a single candidate and values with a single
+// possible key until nothing changes. Returns the value per key or UNMATCHED
+// if still open.
+fn propagate(options: &mut Vec<HashSet<usize>>) -> Result<Vec<usize>, AssignmentError> {
+    let assigned = vec![UNMATCHED; options.len()];
+    let (result, assigned) = fixed_point((mem::take(options), assigned), |(o, a)| {
+        let (mut o, mut a) = (o.clone(), a.clone());
+        propagation_round(&mut o, &mut a);
+        (o, a)
+    });
+    *options = result;
+
+    if (0..options.len()).any(|k| assigned[k] == UNMATCHED && options[k].is_empty()) {
+        return Err(AssignmentError::NoSolution);
+    }
+    Ok(assigned)
+}
+
+fn propagation_round(options: &mut [HashSet<usize>], assigned: &mut [usize]) {
+    for k in 0..options.len() {
+        if assigned[k] == UNMATCHED && options[k].len() == 1 {
+            let v = *options[k].iter().next().unwrap();
+            assign(options, assigned, k, v);
+        }
+    }
+
+    // hidden singles: a value only one open key can take. This is only a
+    // forced move if every remaining value has to be used, i.e. there are
+    // exactly as many open keys as values they can still choose from.
+    let open_keys = (0..options.len())
+        .filter(|k| assigned[*k] == UNMATCHED)
+        .collect::<Vec<_>>();
+    let reachable_values = open_keys
+        .iter()
+        .flat_map(|k| options[*k].iter().copied())
+        .collect::<HashSet<_>>();
+    if open_keys.len() == reachable_values.len() {
+        for v in reachable_values {
+            let mut holders = open_keys
+                .iter()
+                .filter(|k| assigned[**k] == UNMATCHED && options[**k].contains(&v));
+            if let (Some(k), None) = (holders.next(), holders.next()) {
+                assign(options, assigned, *k, v);
+            }
+        }
+    }
+}
+
+fn assign(options: &mut [HashSet<usize>], assigned: &mut [usize], key: usize, value: usize) {
+    assigned[key] = value;
+    for o in options.iter_mut() {
+        o.remove(&value);
+    }
+    options[key] = HashSet::from([value]);
+}
+
+struct BipartiteMatching {
+    adjacency: Vec<Vec<usize>>,
+    match_left: Vec<usize>,
+    match_right: Vec<usize>,
+}
+
+impl BipartiteMatching {
+    fn new(adjacency: Vec<Vec<usize>>, right_count: usize) -> Self {
+        let left_count = adjacency.len();
+        Self {
+            adjacency,
+            match_left: vec![UNMATCHED; left_count],
+            match_right: vec![UNMATCHED; right_count],
+        }
+    }
+
+    // returns the size of a maximum matching
+    fn hopcroft_karp(&mut self) -> usize {
+        let mut size = 0;
+        loop {
+            let dist = self.bfs_layers();
+            let mut found = 0;
+            for l in 0..self.adjacency.len() {
+                if self.match_left[l] == UNMATCHED && self.dfs_layered(l, &dist) {
+                    found += 1;
+                }
+            }
+            if found == 0 {
+                return size;
+            }
+            size += found;
+        }
+    }
+
+    fn bfs_layers(&self) -> Vec<usize> {
+        let mut dist = vec![usize::MAX; self.adjacency.len()];
+        let mut queue = VecDeque::new();
+        for (l, r) in self.match_left.iter().enumerate() {
+            if *r == UNMATCHED {
+                dist[l] = 0;
+                queue.push_back(l);
+            }
+        }
+        while let Some(l) = queue.pop_front() {
+            for r in &self.adjacency[l] {
+                let next = self.match_right[*r];
+                if next != UNMATCHED && dist[next] == usize::MAX {
+                    dist[next] = dist[l] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        dist
+    }
+
+    fn dfs_layered(&mut self, l: usize, dist: &[usize]) -> bool {
+        for i in 0..self.adjacency[l].len() {
+            let r = self.adjacency[l][i];
+            let next = self.match_right[r];
+            if next == UNMATCHED || (dist[next] == dist[l] + 1 && self.dfs_layered(next, dist)) {
+                self.match_left[l] = r;
+                self.match_right[r] = l;
+                return true;
+            }
+        }
+        false
+    }
+
+    // A perfect matching (on the left side) is unique iff no matched edge can
+    // be replaced, i.e. there is no other augmenting path for its left vertex
+    // once that edge is forbidden.
+    fn is_unique(&self) -> bool {
+        (0..self.adjacency.len()).all(|l| {
+            let mut alternative = Self {
+                adjacency: self.adjacency.clone(),
+                match_left: self.match_left.clone(),
+                match_right: self.match_right.clone(),
+            };
+            let r = alternative.match_left[l];
+            alternative.adjacency[l].retain(|x| *x != r);
+            alternative.match_left[l] = UNMATCHED;
+            alternative.match_right[r] = UNMATCHED;
+            let mut visited = vec![false; self.match_right.len()];
+            !alternative.augment(l, &mut visited)
+        })
+    }
+
+    // plain Kuhn augmenting path search
+    fn augment(&mut self, l: usize, visited: &mut [bool]) -> bool {
+        for i in 0..self.adjacency[l].len() {
+            let r = self.adjacency[l][i];
+            if visited[r] {
+                continue;
+            }
+            visited[r] = true;
+            let next = self.match_right[r];
+            if next == UNMATCHED || self.augment(next, visited) {
+                self.match_left[l] = r;
+                self.match_right[r] = l;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_propagation() {
+        // example from day 16
+        let result = solve_assignment(vec![
+            ("class", vec![1, 2]),
+            ("row", vec![0, 1, 2]),
+            ("seat", vec![2]),
+        ]);
+        assert_eq!(
+            result,
+            Ok(HashMap::from([("row", 0), ("class", 1), ("seat", 2)]))
+        );
+
+        // key i can take every value >= i, only the last one is fixed
+        let staircase = (0..20).map(|k| (k, k..20)).rev();
+        let result = solve_assignment(staircase).unwrap();
+        assert!(result.iter().all(|(k, v)| k == v));
+    }
+
+    #[test]
+    fn test_hidden_single() {
+        // d is the only one able to take 4 and has to, as a, b and c can't
+        // share 1, 2 and 3 otherwise. After that a, b and c can rotate.
+        let result = solve_assignment(vec![
+            ('a', vec![1, 2]),
+            ('b', vec![2, 3]),
+            ('c', vec![1, 3]),
+            ('d', vec![1, 2, 3, 4]),
+        ]);
+        assert_eq!(result, Err(AssignmentError::MultipleSolutions));
+
+        let result = solve_assignment(vec![('a', vec![1, 2]), ('b', vec![1, 2, 3])]);
+        assert_eq!(result, Err(AssignmentError::MultipleSolutions));
+
+        let result = solve_assignment(vec![
+            ('a', vec![1, 2, 3]),
+            ('b', vec![1, 2]),
+            ('c', vec![2]),
+        ]);
+        assert_eq!(result, Ok(HashMap::from([('a', 3), ('b', 1), ('c', 2)])));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            solve_assignment(vec![('a', vec![1]), ('b', vec![1])]),
+            Err(AssignmentError::NoSolution)
+        );
+        // pigeonhole, only detected by the matching
+        assert_eq!(
+            solve_assignment(vec![
+                ('a', vec![1, 2]),
+                ('b', vec![1, 2]),
+                ('c', vec![1, 2])
+            ]),
+            Err(AssignmentError::NoSolution)
+        );
+        assert_eq!(
+            solve_assignment(vec![('a', vec![1, 2]), ('b', vec![1, 2])]),
+            Err(AssignmentError::MultipleSolutions)
+        );
+        assert_eq!(
+            solve_assignment(Vec::<(char, Vec<u8>)>::new()),
+            Ok(HashMap::new())
+        );
+    }
+}
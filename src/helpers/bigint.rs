@@ -0,0 +1,807 @@
+use num_traits::{Num, One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+// Small arbitrary precision integers for puzzle answers that do not fit into
+// u64. Both fulfill `Scalar`, so they work with helpers like `egcd` and `crt`
+// and as coordinates of a `Point`. Values live on the heap and aren't `Copy`,
+// which the matrices and most point geometry need.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+const LIMB_BITS: u32 = 32;
+const LIMB_BASE: u64 = 1 << LIMB_BITS;
+const LIMB_MASK: u64 = LIMB_BASE - 1;
+
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    // little endian limbs in base 2^32 without leading (most significant) zeros
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => (self.limbs.len() as u64) * LIMB_BITS as u64 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    // returns (quotient, remainder) of truncated division
+    pub fn divmod(&self, other: &Self) -> (Self, Self) {
+        if other.is_zero() {
+            panic!("attempt to divide by zero");
+        }
+        if self < other {
+            return (Self::zero(), self.clone());
+        }
+        if other.limbs.len() == 1 {
+            let (q, r) = divmod_small(&self.limbs, other.limbs[0]);
+            return (Self::from_limbs(q), Self::from(r));
+        }
+        let (q, r) = divmod_knuth(&self.limbs, &other.limbs);
+        (Self::from_limbs(q), Self::from_limbs(r))
+    }
+
+    fn mul_small_add(&mut self, factor: u32, summand: u32) {
+        let mut carry = summand as u64;
+        for limb in self.limbs.iter_mut() {
+            let p = *limb as u64 * factor as u64 + carry;
+            *limb = p as u32;
+            carry = p >> LIMB_BITS;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+fn add_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+    for (i, l) in long.iter().enumerate() {
+        let s = *l as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        out.push(s as u32);
+        carry = s >> LIMB_BITS;
+    }
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+    out
+}
+
+// a - b, requires a >= b
+fn sub_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, l) in a.iter().enumerate() {
+        let mut d = *l as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if d < 0 {
+            d += LIMB_BASE as i64;
+            borrow = 1;
+        }
+        out.push(d as u32);
+    }
+    assert!(borrow == 0, "attempt to subtract with overflow");
+    out
+}
+
+fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut out = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, y) in b.iter().enumerate() {
+            let p = *x as u64 * *y as u64 + out[i + j] as u64 + carry;
+            out[i + j] = p as u32;
+            carry = p >> LIMB_BITS;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    out
+}
+
+fn divmod_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut q = vec![0u32; a.len()];
+    let mut rem = 0u64;
+    for i in (0..a.len()).rev() {
+        let cur = (rem << LIMB_BITS) | a[i] as u64;
+        q[i] = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+    (q, rem as u32)
+}
+
+fn shl_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return a.to_vec();
+    }
+    let mut out = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for l in a {
+        out.push((l << shift) | carry);
+        carry = l >> (LIMB_BITS - shift);
+    }
+    out.push(carry);
+    out
+}
+
+// Knuth, TAOCP Vol. 2, 4.3.1, Algorithm D. Requires v to have at least two limbs.
+fn divmod_knuth(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let n = v.len();
+    let m = u.len() - n;
+    let shift = v[n - 1].leading_zeros();
+    let vn = &shl_bits(v, shift)[..n];
+    let mut un = shl_bits(u, shift);
+    un.resize(u.len() + 1, 0);
+
+    let mut q = vec![0u32; m + 1];
+    for j in (0..=m).rev() {
+        let num = ((un[j + n] as u64) << LIMB_BITS) | un[j + n - 1] as u64;
+        let mut qhat = num / vn[n - 1] as u64;
+        let mut rhat = num % vn[n - 1] as u64;
+        while qhat >= LIMB_BASE
+            || qhat * vn[n - 2] as u64 > ((rhat << LIMB_BITS) | un[j + n - 2] as u64)
+        {
+            qhat -= 1;
+            rhat += vn[n - 1] as u64;
+            if rhat >= LIMB_BASE {
+                break;
+            }
+        }
+
+        // multiply and subtract qhat * vn from the current window of un
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = qhat * vn[i] as u64 + carry;
+            carry = p >> LIMB_BITS;
+            let mut t = un[i + j] as i64 - borrow - (p & LIMB_MASK) as i64;
+            borrow = 0;
+            if t < 0 {
+                t += LIMB_BASE as i64;
+                borrow = 1;
+            }
+            un[i + j] = t as u32;
+        }
+        let t = un[j + n] as i64 - carry as i64 - borrow;
+        un[j + n] = t as u32;
+
+        q[j] = qhat as u32;
+        if t < 0 {
+            // qhat was one too large, add back
+            q[j] -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let s = un[i + j] as u64 + vn[i] as u64 + carry;
+                un[i + j] = s as u32;
+                carry = s >> LIMB_BITS;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+    }
+
+    let mut r = vec![0u32; n];
+    for i in 0..n {
+        r[i] = if shift == 0 {
+            un[i]
+        } else {
+            (un[i] >> shift) | (un[i + 1] << (LIMB_BITS - shift))
+        };
+    }
+    (q, r)
+}
+
+fn parse_magnitude(s: &str, radix: u32) -> Result<BigUint, ParseBigIntError> {
+    if s.is_empty() || !(2..=36).contains(&radix) {
+        return Err(ParseBigIntError);
+    }
+    let mut result = BigUint::zero();
+    let mut digits = 0;
+    for c in s.chars() {
+        if c == '_' {
+            continue;
+        }
+        let digit = c.to_digit(radix).ok_or(ParseBigIntError)?;
+        result.mul_small_add(radix, digit);
+        digits += 1;
+    }
+    // underscores only separate digits
+    if digits == 0 {
+        return Err(ParseBigIntError);
+    }
+    Ok(result)
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+        // peel off chunks of 9 decimal digits
+        let mut chunks = vec![];
+        let mut rest = self.limbs.clone();
+        while !rest.is_empty() {
+            let (q, r) = divmod_small(&rest, 1_000_000_000);
+            chunks.push(r);
+            rest = BigUint::from_limbs(q).limbs;
+        }
+        let mut s = chunks.pop().unwrap().to_string();
+        for c in chunks.iter().rev() {
+            s.push_str(&format!("{:09}", c));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_magnitude(s.strip_prefix('+').unwrap_or(s), 10)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::from_limbs(vec![value as u32, (value >> LIMB_BITS) as u32])
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        Self::from_limbs((0..4).map(|i| (value >> (32 * i)) as u32).collect())
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(value: usize) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+impl ToPrimitive for BigUint {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_u64().and_then(|v| v.try_into().ok())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|v| v.try_into().ok())
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, l)| acc | (*l as u128) << (32 * i)),
+        )
+    }
+}
+
+impl Zero for BigUint {
+    fn zero() -> Self {
+        Self { limbs: vec![] }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+}
+
+impl One for BigUint {
+    fn one() -> Self {
+        Self { limbs: vec![1] }
+    }
+}
+
+impl Num for BigUint {
+    type FromStrRadixErr = ParseBigIntError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        parse_magnitude(str, radix)
+    }
+}
+
+fn biguint_add(a: &BigUint, b: &BigUint) -> BigUint {
+    BigUint::from_limbs(add_limbs(&a.limbs, &b.limbs))
+}
+
+fn biguint_sub(a: &BigUint, b: &BigUint) -> BigUint {
+    BigUint::from_limbs(sub_limbs(&a.limbs, &b.limbs))
+}
+
+fn biguint_mul(a: &BigUint, b: &BigUint) -> BigUint {
+    BigUint::from_limbs(mul_limbs(&a.limbs, &b.limbs))
+}
+
+fn biguint_div(a: &BigUint, b: &BigUint) -> BigUint {
+    a.divmod(b).0
+}
+
+fn biguint_rem(a: &BigUint, b: &BigUint) -> BigUint {
+    a.divmod(b).1
+}
+
+// implements a binary operator for all owned/borrowed combinations plus the
+// matching assign operator on top of a function working on references
+macro_rules! forward_binop {
+    ($t:ty, $imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident, $func:ident) => {
+        impl $imp<&$t> for &$t {
+            type Output = $t;
+            fn $method(self, rhs: &$t) -> $t {
+                $func(self, rhs)
+            }
+        }
+
+        impl $imp<$t> for &$t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                $func(self, &rhs)
+            }
+        }
+
+        impl $imp<&$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: &$t) -> $t {
+                $func(&self, rhs)
+            }
+        }
+
+        impl $imp<$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                $func(&self, &rhs)
+            }
+        }
+
+        impl $assign_imp<&$t> for $t {
+            fn $assign_method(&mut self, rhs: &$t) {
+                *self = $func(self, rhs);
+            }
+        }
+
+        impl $assign_imp<$t> for $t {
+            fn $assign_method(&mut self, rhs: $t) {
+                *self = $func(self, &rhs);
+            }
+        }
+    };
+}
+
+forward_binop!(BigUint, Add, add, AddAssign, add_assign, biguint_add);
+forward_binop!(BigUint, Sub, sub, SubAssign, sub_assign, biguint_sub);
+forward_binop!(BigUint, Mul, mul, MulAssign, mul_assign, biguint_mul);
+forward_binop!(BigUint, Div, div, DivAssign, div_assign, biguint_div);
+forward_binop!(BigUint, Rem, rem, RemAssign, rem_assign, biguint_rem);
+
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    // zero is never negative
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn into_magnitude(self) -> BigUint {
+        self.magnitude
+    }
+
+    pub fn pow(&self, exp: u32) -> Self {
+        Self::from_parts(self.negative && exp % 2 == 1, self.magnitude.pow(exp))
+    }
+
+    // returns (quotient, remainder) of truncated division, just like `/` and `%`
+    // on the primitive integers: the remainder has the sign of `self`
+    pub fn divmod(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = self.magnitude.divmod(&other.magnitude);
+        (
+            Self::from_parts(self.negative != other.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+
+    pub fn rem_euclid(&self, other: &Self) -> Self {
+        let r = self % other;
+        if r.negative {
+            r + other.abs()
+        } else {
+            r
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(value: BigUint) -> Self {
+        Self::from_parts(false, value)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        Self::from_parts(value < 0, BigUint::from(value.unsigned_abs()))
+    }
+}
+
+macro_rules! bigint_from_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(value: $t) -> Self {
+                    Self::from(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+bigint_from_primitive!(i32, i64, isize, u32, u64, usize);
+
+impl ToPrimitive for BigInt {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_i128().and_then(|v| v.try_into().ok())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_i128().and_then(|v| v.try_into().ok())
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        let m = self.magnitude.to_u128()?;
+        if !self.negative {
+            return m.try_into().ok();
+        }
+        // one more negative value than positive ones, i128::MIN
+        if m > 1 << 127 {
+            return None;
+        }
+        Some((m as i128).wrapping_neg())
+    }
+}
+
+impl Zero for BigInt {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+}
+
+impl One for BigInt {
+    fn one() -> Self {
+        Self::from_parts(false, BigUint::one())
+    }
+}
+
+impl Num for BigInt {
+    type FromStrRadixErr = ParseBigIntError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        let (negative, digits) = match str.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, str.strip_prefix('+').unwrap_or(str)),
+        };
+        Ok(Self::from_parts(negative, parse_magnitude(digits, radix)?))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        Self::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+impl Signed for BigInt {
+    fn abs(&self) -> Self {
+        Self::from_parts(false, self.magnitude.clone())
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        if self <= other {
+            Self::zero()
+        } else {
+            self - other
+        }
+    }
+
+    fn signum(&self) -> Self {
+        if self.is_zero() {
+            Self::zero()
+        } else {
+            Self::from_parts(self.negative, BigUint::one())
+        }
+    }
+
+    fn is_positive(&self) -> bool {
+        !self.negative && !self.is_zero()
+    }
+
+    fn is_negative(&self) -> bool {
+        self.negative
+    }
+}
+
+fn bigint_add(a: &BigInt, b: &BigInt) -> BigInt {
+    if a.negative == b.negative {
+        return BigInt::from_parts(a.negative, &a.magnitude + &b.magnitude);
+    }
+    match a.magnitude.cmp(&b.magnitude) {
+        Ordering::Less => BigInt::from_parts(b.negative, &b.magnitude - &a.magnitude),
+        _ => BigInt::from_parts(a.negative, &a.magnitude - &b.magnitude),
+    }
+}
+
+fn bigint_sub(a: &BigInt, b: &BigInt) -> BigInt {
+    bigint_add(a, &-b)
+}
+
+fn bigint_mul(a: &BigInt, b: &BigInt) -> BigInt {
+    BigInt::from_parts(a.negative != b.negative, &a.magnitude * &b.magnitude)
+}
+
+fn bigint_div(a: &BigInt, b: &BigInt) -> BigInt {
+    a.divmod(b).0
+}
+
+fn bigint_rem(a: &BigInt, b: &BigInt) -> BigInt {
+    a.divmod(b).1
+}
+
+forward_binop!(BigInt, Add, add, AddAssign, add_assign, bigint_add);
+forward_binop!(BigInt, Sub, sub, SubAssign, sub_assign, bigint_sub);
+forward_binop!(BigInt, Mul, mul, MulAssign, mul_assign, bigint_mul);
+forward_binop!(BigInt, Div, div, DivAssign, div_assign, bigint_div);
+forward_binop!(BigInt, Rem, rem, RemAssign, rem_assign, bigint_rem);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::Point3;
+
+    #[test]
+    fn test_scalar_points() {
+        let big = |s: &str| s.parse::<BigInt>().unwrap();
+        let a = Point3::new(big("100000000000000000000"), big("-3"), BigInt::one());
+        let b = Point3::new(big("1"), big("4"), big("-100000000000000000000"));
+        assert_eq!(
+            a.clone() + b.clone(),
+            Point3::new(
+                big("100000000000000000001"),
+                big("1"),
+                big("-99999999999999999999")
+            )
+        );
+        assert_eq!(a.dot(&b), big("-12"));
+        assert_eq!((a * big("-2"))[0], big("-200000000000000000000"));
+        assert!(Point3::<BigUint>::zero().is_zero());
+    }
+
+    // deterministic pseudo random numbers for comparing against u128
+    fn lcg(seed: &mut u64) -> u64 {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        *seed >> (*seed % 64)
+    }
+
+    #[test]
+    fn test_biguint_matches_u128() {
+        let mut seed = 42;
+        for _ in 0..2000 {
+            let a = lcg(&mut seed) as u128 * lcg(&mut seed) as u128 + lcg(&mut seed) as u128;
+            let b = lcg(&mut seed) as u128 + 1;
+            let (ba, bb) = (BigUint::from(a), BigUint::from(b));
+            assert_eq!((&ba + &bb).to_u128(), a.checked_add(b));
+            assert_eq!((&ba / &bb).to_u128(), Some(a / b));
+            assert_eq!((&ba % &bb).to_u128(), Some(a % b));
+            if a >= b {
+                assert_eq!((&ba - &bb).to_u128(), Some(a - b));
+            }
+            if let Some(p) = a.checked_mul(b) {
+                assert_eq!((&ba * &bb).to_u128(), Some(p));
+            }
+            // multi-limb divisor
+            let c = (b << 40) | 0xdead_beef;
+            let (q, r) = ba.divmod(&BigUint::from(c));
+            assert_eq!((q.to_u128(), r.to_u128()), (Some(a / c), Some(a % c)));
+        }
+    }
+
+    #[test]
+    fn test_biguint_divmod_large() {
+        let a: BigUint = "123456789012345678901234567890123456789012345678901234567890"
+            .parse()
+            .unwrap();
+        let b: BigUint = "98765432109876543210987654321".parse().unwrap();
+        let (q, r) = a.divmod(&b);
+        assert!(r < b);
+        assert_eq!(&q * &b + &r, a);
+        assert_eq!(q.to_string(), "1249999988609375000142382812499");
+    }
+
+    #[test]
+    fn test_display_and_parse() {
+        let factorial_30 = (1..=30u32).fold(BigUint::one(), |acc, n| acc * BigUint::from(n));
+        assert_eq!(
+            factorial_30.to_string(),
+            "265252859812191058636308480000000"
+        );
+        assert_eq!(
+            factorial_30.to_string().parse::<BigUint>(),
+            Ok(factorial_30)
+        );
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigIntError));
+        assert_eq!("_".parse::<BigUint>(), Err(ParseBigIntError));
+        assert_eq!("-__".parse::<BigInt>(), Err(ParseBigIntError));
+        assert_eq!("1_000".parse::<BigInt>(), Ok(BigInt::from(1000)));
+        assert_eq!(BigUint::from_str_radix("ff", 16), Ok(BigUint::from(255u32)));
+        assert_eq!(
+            BigUint::from(2u32).pow(100).to_string(),
+            "1267650600228229401496703205376"
+        );
+    }
+
+    #[test]
+    fn test_to_i128() {
+        for n in [i128::MIN, i128::MIN + 1, -1, 0, i128::MAX] {
+            assert_eq!(BigInt::from(n).to_i128(), Some(n));
+        }
+        let below_min = BigInt::from(i128::MIN) - BigInt::one();
+        assert_eq!(below_min.to_i128(), None);
+        assert_eq!((BigInt::from(i128::MAX) + BigInt::one()).to_i128(), None);
+        assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+    }
+
+    #[test]
+    fn test_bigint_signs() {
+        let a = BigInt::from(-7);
+        let b = BigInt::from(2);
+        assert_eq!(&a / &b, BigInt::from(-3));
+        assert_eq!(&a % &b, BigInt::from(-1));
+        assert_eq!(a.rem_euclid(&b), BigInt::from(1));
+        assert_eq!(&a + &b, BigInt::from(-5));
+        assert_eq!(&b - &a, BigInt::from(9));
+        assert_eq!(&a * &a, BigInt::from(49));
+        assert_eq!(a.pow(3), BigInt::from(-343));
+        assert_eq!(&a + BigInt::from(7), BigInt::zero());
+        assert!(!(&a + BigInt::from(7)).is_negative());
+        assert_eq!(a.abs(), BigInt::from(7));
+        assert!(a < b);
+        assert_eq!(
+            "-12345678901234567890"
+                .parse::<BigInt>()
+                .unwrap()
+                .to_string(),
+            "-12345678901234567890"
+        );
+        assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+    }
+}
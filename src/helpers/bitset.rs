@@ -0,0 +1,424 @@
+use std::fmt;
+use std::ops;
+
+// Sets of small non-negative integers stored as bits: answers 'a'..='z',
+// the bits of a mask. `Bitset` has a fixed size of WORDS * 64 bits and is
+// Copy, `GrowableBitset` grows to fit the largest element inserted.
+
+const BITS: usize = 64;
+
+fn count(words: &[u64]) -> usize {
+    words.iter().map(|w| w.count_ones() as usize).sum()
+}
+
+fn contains(words: &[u64], i: usize) -> bool {
+    words
+        .get(i / BITS)
+        .is_some_and(|w| w & (1 << (i % BITS)) != 0)
+}
+
+// the set bits in increasing order
+pub struct Ones<'a> {
+    words: &'a [u64],
+    index: usize,
+    current: u64,
+}
+
+impl<'a> Ones<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        Self {
+            words,
+            index: 0,
+            current: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // clear the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.index * BITS + bit)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bitset<const WORDS: usize>([u64; WORDS]);
+
+impl<const WORDS: usize> Default for Bitset<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WORDS: usize> Bitset<WORDS> {
+    pub const CAPACITY: usize = WORDS * BITS;
+
+    pub fn new() -> Self {
+        Self([0; WORDS])
+    }
+
+    pub fn from_words(words: [u64; WORDS]) -> Self {
+        Self(words)
+    }
+
+    pub fn words(&self) -> &[u64; WORDS] {
+        &self.0
+    }
+
+    // panics if i doesn't fit
+    pub fn insert(&mut self, i: usize) {
+        self.0[i / BITS] |= 1 << (i % BITS);
+    }
+
+    pub fn remove(&mut self, i: usize) {
+        if i < Self::CAPACITY {
+            self.0[i / BITS] &= !(1 << (i % BITS));
+        }
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        contains(&self.0, i)
+    }
+
+    // number of elements
+    pub fn count(&self) -> usize {
+        count(&self.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|w| *w == 0)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        *self | *other
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        *self & *other
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        *self & !*other
+    }
+
+    // the elements in exactly one of both
+    pub fn xor(&self, other: &Self) -> Self {
+        *self ^ *other
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn iter(&self) -> Ones<'_> {
+        Ones::new(&self.0)
+    }
+
+    // All 2^count subsets, starting with the empty set and counting up as if
+    // the chosen bits were the only ones. Used to fill in floating bits.
+    pub fn subsets(&self) -> Subsets<WORDS> {
+        Subsets {
+            mask: *self,
+            next: Some(Self::new()),
+        }
+    }
+}
+
+pub struct Subsets<const WORDS: usize> {
+    mask: Bitset<WORDS>,
+    next: Option<Bitset<WORDS>>,
+}
+
+impl<const WORDS: usize> Iterator for Subsets<WORDS> {
+    type Item = Bitset<WORDS>;
+
+    // Setting every bit outside of the mask before adding one lets the carry
+    // run straight through them to the next bit of the mask.
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        let mut next = current | !self.mask;
+        let mut carry = true;
+        for w in next.0.iter_mut() {
+            (*w, carry) = w.overflowing_add(carry as u64);
+            if !carry {
+                break;
+            }
+        }
+        let next = next & self.mask;
+        self.next = (!next.is_empty()).then_some(next);
+        Some(current)
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for Bitset<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for i in iter {
+            result.insert(i);
+        }
+        result
+    }
+}
+
+impl From<u64> for Bitset<1> {
+    fn from(value: u64) -> Self {
+        Self([value])
+    }
+}
+
+impl From<Bitset<1>> for u64 {
+    fn from(value: Bitset<1>) -> Self {
+        value.0[0]
+    }
+}
+
+impl<const WORDS: usize> ops::BitOr for Bitset<WORDS> {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| self.0[i] | rhs.0[i]))
+    }
+}
+
+impl<const WORDS: usize> ops::BitAnd for Bitset<WORDS> {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| self.0[i] & rhs.0[i]))
+    }
+}
+
+impl<const WORDS: usize> ops::BitXor for Bitset<WORDS> {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| self.0[i] ^ rhs.0[i]))
+    }
+}
+
+impl<const WORDS: usize> ops::Not for Bitset<WORDS> {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        Self(self.0.map(|w| !w))
+    }
+}
+
+impl<const WORDS: usize> ops::BitOrAssign for Bitset<WORDS> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl<const WORDS: usize> ops::BitAndAssign for Bitset<WORDS> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = *self & rhs;
+    }
+}
+
+impl<const WORDS: usize> ops::BitXorAssign for Bitset<WORDS> {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        *self = *self ^ rhs;
+    }
+}
+
+impl<const WORDS: usize> fmt::Debug for Bitset<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+// highest bit first, like the binary representation of a number
+impl<const WORDS: usize> fmt::Binary for Bitset<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self
+            .0
+            .iter()
+            .rev()
+            .map(|w| format!("{:064b}", w))
+            .collect::<String>();
+        let digits = digits.trim_start_matches('0');
+        f.pad_integral(true, "0b", if digits.is_empty() { "0" } else { digits })
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct GrowableBitset(Vec<u64>);
+
+impl GrowableBitset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // room for 0..bits without growing
+    pub fn with_capacity(bits: usize) -> Self {
+        Self(Vec::with_capacity(bits.div_ceil(BITS)))
+    }
+
+    pub fn insert(&mut self, i: usize) {
+        if i / BITS >= self.0.len() {
+            self.0.resize(i / BITS + 1, 0);
+        }
+        self.0[i / BITS] |= 1 << (i % BITS);
+    }
+
+    pub fn remove(&mut self, i: usize) {
+        if let Some(w) = self.0.get_mut(i / BITS) {
+            *w &= !(1 << (i % BITS));
+        }
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        contains(&self.0, i)
+    }
+
+    pub fn count(&self) -> usize {
+        count(&self.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|w| *w == 0)
+    }
+
+    // combines word by word, words missing from the shorter set count as 0
+    fn zip_with(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        let len = self.0.len().max(other.0.len());
+        let word = |words: &[u64], i: usize| words.get(i).copied().unwrap_or(0);
+        Self(
+            (0..len)
+                .map(|i| f(word(&self.0, i), word(&other.0, i)))
+                .collect(),
+        )
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    pub fn xor(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn iter(&self) -> Ones<'_> {
+        Ones::new(&self.0)
+    }
+}
+
+// equal if they hold the same elements, however many words they have
+impl PartialEq for GrowableBitset {
+    fn eq(&self, other: &Self) -> bool {
+        self.xor(other).is_empty()
+    }
+}
+
+impl Eq for GrowableBitset {}
+
+impl FromIterator<usize> for GrowableBitset {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for i in iter {
+            result.insert(i);
+        }
+        result
+    }
+}
+
+impl fmt::Debug for GrowableBitset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_set_operations() {
+        let a: Bitset<2> = [1, 5, 64, 100].into_iter().collect();
+        let b: Bitset<2> = [5, 63, 100, 127].into_iter().collect();
+        assert_eq!(a.count(), 4);
+        assert!(a.contains(64));
+        assert!(!a.contains(63));
+        assert!(!a.contains(1000));
+        assert_eq!(
+            a.union(&b).iter().collect::<Vec<_>>(),
+            vec![1, 5, 63, 64, 100, 127]
+        );
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![5, 100]);
+        assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), vec![1, 64]);
+        assert_eq!(a.xor(&b).count(), 4);
+        assert!(a.intersection(&b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+
+        let mut c = a;
+        c.remove(64);
+        c.remove(1000);
+        c |= Bitset::from_words([0, 1]);
+        assert_eq!(c, a);
+        assert_eq!(format!("{:?}", Bitset::<1>::from(0b1010)), "{1, 3}");
+        assert_eq!(
+            format!("{:b}", Bitset::<2>::from_words([5, 1])),
+            format!("1{:064b}", 5)
+        );
+        assert!(Bitset::<3>::new().is_empty());
+        assert_eq!(Bitset::<3>::CAPACITY, 192);
+    }
+
+    #[test]
+    fn test_subsets() {
+        let mask = Bitset::<1>::from(0b101100);
+        let subsets = mask.subsets().map(u64::from).collect::<Vec<_>>();
+        assert_eq!(
+            subsets,
+            vec![0, 0b100, 0b1000, 0b1100, 0b100000, 0b100100, 0b101000, 0b101100]
+        );
+        assert_eq!(Bitset::<1>::new().subsets().count(), 1);
+
+        // carries across words, and every subset only shows up once
+        let wide: Bitset<2> = [3, 63, 64, 120].into_iter().collect();
+        let subsets = wide.subsets().collect::<HashSet<_>>();
+        assert_eq!(subsets.len(), 16);
+        assert!(subsets.iter().all(|s| s.is_subset(&wide)));
+
+        let full = Bitset::<1>::from(u64::MAX);
+        assert_eq!(full.subsets().nth(1 << 20), Some(Bitset::from(1 << 20)));
+    }
+
+    #[test]
+    fn test_growable() {
+        let mut a = GrowableBitset::with_capacity(10);
+        a.insert(3);
+        a.insert(200);
+        let b: GrowableBitset = [3, 4].into_iter().collect();
+        assert_eq!(a.count(), 2);
+        assert!(a.contains(200));
+        assert!(!b.contains(200));
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![3, 4, 200]);
+        assert_eq!(a.intersection(&b), [3].into_iter().collect());
+        assert_eq!(a.xor(&b).iter().collect::<Vec<_>>(), vec![4, 200]);
+
+        // trailing empty words don't matter
+        a.remove(200);
+        assert_eq!(a, [3].into_iter().collect());
+        assert!(a.is_subset(&b));
+        assert_eq!(format!("{:?}", a), "{3}");
+    }
+}
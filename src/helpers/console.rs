@@ -0,0 +1,603 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use super::graph::{Graph, Traversable};
+
+// The handheld game console: an accumulator, a program counter and three
+// instructions. Runs step by step with an optional trace, stops on loops and
+// breakpoints and can find the one instruction to flip to fix a program.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbortState {
+    Running,
+    AbortByLoop,
+    AbortByEnd,
+    // stopped before running the instruction at the breakpoint
+    AbortByBreakpoint(usize),
+    // a jump to before the first instruction or past the end of the program
+    AbortByInvalidJump(isize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Noop(i32),
+    Accumulator(i32),
+    Jump(i32),
+}
+
+impl Operation {
+    pub fn new(mnemonic: &str, argument: i32) -> Option<Self> {
+        match mnemonic {
+            "nop" => Some(Self::Noop(argument)),
+            "acc" => Some(Self::Accumulator(argument)),
+            "jmp" => Some(Self::Jump(argument)),
+            _ => None,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::Noop(_) => "nop",
+            Self::Accumulator(_) => "acc",
+            Self::Jump(_) => "jmp",
+        }
+    }
+
+    pub fn argument(&self) -> i32 {
+        match self {
+            Self::Noop(n) | Self::Accumulator(n) | Self::Jump(n) => *n,
+        }
+    }
+
+    // where the program continues after running this at pc
+    pub fn next(&self, pc: usize) -> isize {
+        match self {
+            Self::Jump(n) => pc as isize + *n as isize,
+            _ => pc as isize + 1,
+        }
+    }
+
+    // nop and jmp swapped, None for acc
+    pub fn flipped(&self) -> Option<Self> {
+        match self {
+            Self::Noop(n) => Some(Self::Jump(*n)),
+            Self::Jump(n) => Some(Self::Noop(*n)),
+            Self::Accumulator(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{} {:+}", self.mnemonic(), self.argument()))
+    }
+}
+
+impl TryFrom<&str> for Operation {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (op, arg) = value.split_once(' ').ok_or(())?;
+        let n = arg.parse::<i32>().map_err(|_| ())?;
+        Self::new(op, n).ok_or(())
+    }
+}
+
+// a line of code that isn't an instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseProgramError {
+    // starting at 0
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for ParseProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid instruction {:?} at line {}",
+            self.text, self.line
+        )
+    }
+}
+
+impl std::error::Error for ParseProgramError {}
+
+// When running an instruction again counts as a loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopDetection {
+    Off,
+    // stop before an instruction would run for the (n + 1)th time
+    AfterVisits(u32),
+}
+
+impl Default for LoopDetection {
+    fn default() -> Self {
+        Self::AfterVisits(1)
+    }
+}
+
+// one executed instruction, with the accumulator after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub program_counter: usize,
+    pub operation: Operation,
+    pub accumulator: i32,
+}
+
+// the instruction to flip and the accumulator once the fixed program ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repair {
+    pub index: usize,
+    pub accumulator: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct CPU {
+    // wraps around on overflow like a 32 bit register, which only matters
+    // with loop detection off
+    accumulator: i32,
+    program_counter: usize,
+    instructions: Vec<Operation>,
+    visits: Vec<u32>,
+    loop_detection: LoopDetection,
+    breakpoints: HashSet<usize>,
+    // stopped at the breakpoint at the program counter, the next step runs it
+    paused: bool,
+    trace: Option<Vec<Step>>,
+}
+
+impl CPU {
+    pub fn new(instructions: Vec<Operation>) -> CPU {
+        CPU {
+            accumulator: 0,
+            program_counter: 0,
+            visits: vec![0; instructions.len()],
+            instructions,
+            loop_detection: LoopDetection::default(),
+            breakpoints: HashSet::new(),
+            paused: false,
+            trace: None,
+        }
+    }
+
+    pub fn new_from_code(code: &str) -> Result<CPU, ParseProgramError> {
+        let instructions = code
+            .lines()
+            .enumerate()
+            .map(|(line, text)| {
+                Operation::try_from(text).map_err(|_| ParseProgramError {
+                    line,
+                    text: text.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(instructions))
+    }
+
+    pub fn loop_detection(mut self, loop_detection: LoopDetection) -> Self {
+        self.loop_detection = loop_detection;
+        self
+    }
+
+    // keeps every executed step, see `trace`
+    pub fn traced(mut self) -> Self {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    pub fn accumulator(&self) -> i32 {
+        self.accumulator
+    }
+
+    pub fn program_counter(&self) -> usize {
+        self.program_counter
+    }
+
+    pub fn instructions(&self) -> &[Operation] {
+        &self.instructions
+    }
+
+    // the steps since the last reset, empty if tracing is off
+    pub fn trace(&self) -> &[Step] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn add_breakpoint(&mut self, index: usize) {
+        self.breakpoints.insert(index);
+    }
+
+    pub fn remove_breakpoint(&mut self, index: usize) -> bool {
+        self.breakpoints.remove(&index)
+    }
+
+    // back to the start, keeping program, breakpoints and settings
+    pub fn reset(&mut self) {
+        self.accumulator = 0;
+        self.program_counter = 0;
+        self.paused = false;
+        self.visits.iter_mut().for_each(|v| *v = 0);
+        if let Some(trace) = &mut self.trace {
+            trace.clear();
+        }
+    }
+
+    pub fn flip_inst(&mut self, index: usize) -> bool {
+        match self.instructions[index].flipped() {
+            Some(op) => {
+                self.instructions[index] = op;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // run an instruction and return if the program can go on with the next one
+    pub fn run_step(&mut self) -> AbortState {
+        let Some(op) = self.instructions.get(self.program_counter).copied() else {
+            return AbortState::AbortByEnd;
+        };
+        if !self.paused && self.breakpoints.contains(&self.program_counter) {
+            self.paused = true;
+            return AbortState::AbortByBreakpoint(self.program_counter);
+        }
+        self.paused = false;
+        self.visits[self.program_counter] += 1;
+        if let Operation::Accumulator(n) = op {
+            self.accumulator = self.accumulator.wrapping_add(n);
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.push(Step {
+                program_counter: self.program_counter,
+                operation: op,
+                accumulator: self.accumulator,
+            });
+        }
+
+        let next = op.next(self.program_counter);
+        if next < 0 || next as usize > self.instructions.len() {
+            return AbortState::AbortByInvalidJump(next);
+        }
+        self.program_counter = next as usize;
+
+        if self.program_counter == self.instructions.len() {
+            return AbortState::AbortByEnd;
+        }
+        if let LoopDetection::AfterVisits(n) = self.loop_detection {
+            if self.visits[self.program_counter] >= n {
+                return AbortState::AbortByLoop;
+            }
+        }
+        AbortState::Running
+    }
+
+    // Runs until the program stops for whatever reason. After a breakpoint
+    // it simply continues from there.
+    pub fn run(&mut self) -> AbortState {
+        loop {
+            let state = self.run_step();
+            if state != AbortState::Running {
+                return state;
+            }
+        }
+    }
+
+    // the program, one instruction per line, with where every jump goes
+    pub fn disassemble(&self) -> String {
+        let width = self.instructions.len().to_string().len();
+        let mut result = String::new();
+        for (i, op) in self.instructions.iter().enumerate() {
+            let marker = if self.breakpoints.contains(&i) {
+                '*'
+            } else {
+                ' '
+            };
+            let line = match op {
+                Operation::Jump(_) => {
+                    let target = match op.next(i) {
+                        t if t == self.instructions.len() as isize => String::from("end"),
+                        t if t < 0 || t > self.instructions.len() as isize => {
+                            String::from("out of range")
+                        }
+                        t => t.to_string(),
+                    };
+                    format!("{}{:>width$}  {:<10}-> {}", marker, i, op, target)
+                }
+                _ => format!("{}{:>width$}  {}", marker, i, op),
+            };
+            result.push_str(&line);
+            result.push('\n');
+        }
+        result
+    }
+
+    // The one nop or jmp to flip so that the program ends instead of looping,
+    // None if there is none or the program already ends. Every instruction
+    // has a single successor, so the instructions that lead to the end are
+    // the ones reachable from it backwards. The flip has to be on the path
+    // the program takes now and send it to one of those.
+    pub fn repair(&self) -> Option<Repair> {
+        let end = self.instructions.len();
+        let mut graph = Graph::new();
+        graph.add_node(end);
+        for (i, op) in self.instructions.iter().enumerate() {
+            let next = op.next(i);
+            if (0..=end as isize).contains(&next) {
+                graph.add_edge(i, next as usize, ());
+            }
+        }
+        let finishing = graph.reversed().reachable(&end);
+        if finishing.contains(&0) {
+            return None;
+        }
+
+        let mut seen = HashSet::new();
+        let mut pc = 0;
+        while pc < end && seen.insert(pc) {
+            let op = self.instructions[pc];
+            if let Some(flipped) = op.flipped() {
+                let next = flipped.next(pc);
+                if next >= 0 && finishing.contains(&(next as usize)) {
+                    let mut fixed = CPU::new(self.instructions.clone());
+                    fixed.flip_inst(pc);
+                    return match fixed.run() {
+                        AbortState::AbortByEnd => Some(Repair {
+                            index: pc,
+                            accumulator: fixed.accumulator,
+                        }),
+                        _ => None,
+                    };
+                }
+            }
+            let next = op.next(pc);
+            if next < 0 {
+                break;
+            }
+            pc = next as usize;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::random::Random;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    // every flip tried with a full run
+    fn repair_by_trying(cpu: &CPU) -> Option<Repair> {
+        let mut cpu = cpu.clone();
+        for index in 0..cpu.instructions().len() {
+            if !cpu.flip_inst(index) {
+                continue;
+            }
+            cpu.reset();
+            if cpu.run() == AbortState::AbortByEnd {
+                return Some(Repair {
+                    index,
+                    accumulator: cpu.accumulator(),
+                });
+            }
+            cpu.flip_inst(index);
+        }
+        None
+    }
+
+    #[test]
+    fn test_cpu_flip() {
+        let mut cpu_nop = CPU::new_from_code("nop +5").unwrap();
+        assert!(cpu_nop.flip_inst(0));
+        assert_eq!(cpu_nop.instructions()[0], Operation::Jump(5));
+        let mut cpu_acc = CPU::new_from_code("acc +5").unwrap();
+        assert!(!cpu_acc.flip_inst(0));
+        assert_eq!(cpu_acc.instructions()[0], Operation::Accumulator(5));
+        let mut cpu_jmp = CPU::new_from_code("jmp +5").unwrap();
+        assert!(cpu_jmp.flip_inst(0));
+        assert_eq!(cpu_jmp.instructions()[0], Operation::Noop(5));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Operation::try_from("acc -99"),
+            Ok(Operation::Accumulator(-99))
+        );
+        assert_eq!(Operation::try_from("jmp +4"), Ok(Operation::Jump(4)));
+        assert_eq!(Operation::try_from("mul +4"), Err(()));
+        assert_eq!(Operation::try_from("nop"), Err(()));
+        assert_eq!(Operation::try_from("nop x"), Err(()));
+        assert_eq!(Operation::Jump(-3).to_string(), "jmp -3");
+    }
+
+    #[test]
+    fn test_run_and_trace() {
+        let mut cpu = CPU::new_from_code(EXAMPLE).unwrap().traced();
+        assert_eq!(cpu.run(), AbortState::AbortByLoop);
+        assert_eq!(cpu.accumulator(), 5);
+        assert_eq!(cpu.program_counter(), 1);
+        let visited = cpu
+            .trace()
+            .iter()
+            .map(|s| s.program_counter)
+            .collect::<Vec<_>>();
+        assert_eq!(visited, vec![0, 1, 2, 6, 7, 3, 4]);
+        assert_eq!(
+            cpu.trace()[5],
+            Step {
+                program_counter: 3,
+                operation: Operation::Accumulator(3),
+                accumulator: 5,
+            }
+        );
+
+        cpu.reset();
+        assert!(cpu.trace().is_empty());
+        assert_eq!(cpu.run_step(), AbortState::Running);
+        assert_eq!(cpu.trace().len(), 1);
+        assert!(CPU::new_from_code(EXAMPLE).unwrap().trace().is_empty());
+    }
+
+    #[test]
+    fn test_loop_detection() {
+        let mut cpu = CPU::new_from_code(EXAMPLE)
+            .unwrap()
+            .loop_detection(LoopDetection::AfterVisits(3));
+        assert_eq!(cpu.run(), AbortState::AbortByLoop);
+        // three times around the loop of 6 instructions that adds 1 + 1 + 3
+        assert_eq!(cpu.accumulator(), 15);
+
+        let mut cpu = CPU::new_from_code("acc +1\njmp -1")
+            .unwrap()
+            .loop_detection(LoopDetection::Off);
+        for _ in 0..1000 {
+            assert_eq!(cpu.run_step(), AbortState::Running);
+        }
+        assert_eq!(cpu.accumulator(), 500);
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut cpu = CPU::new_from_code(EXAMPLE).unwrap();
+        cpu.add_breakpoint(6);
+        cpu.add_breakpoint(4);
+        assert_eq!(cpu.run(), AbortState::AbortByBreakpoint(6));
+        assert_eq!(cpu.accumulator(), 1);
+        assert_eq!(cpu.run(), AbortState::AbortByBreakpoint(4));
+        assert_eq!(cpu.accumulator(), 5);
+        assert!(cpu.remove_breakpoint(6));
+        assert!(!cpu.remove_breakpoint(6));
+        assert_eq!(cpu.run(), AbortState::AbortByLoop);
+
+        // stops before the very first instruction too, then runs it
+        let mut cpu = CPU::new_from_code("acc +1\nacc +2").unwrap();
+        cpu.add_breakpoint(0);
+        assert_eq!(cpu.run_step(), AbortState::AbortByBreakpoint(0));
+        assert_eq!(cpu.accumulator(), 0);
+        assert_eq!(cpu.run(), AbortState::AbortByEnd);
+        assert_eq!(cpu.accumulator(), 3);
+        cpu.reset();
+        assert_eq!(cpu.run(), AbortState::AbortByBreakpoint(0));
+    }
+
+    #[test]
+    fn test_parse_program() {
+        assert_eq!(
+            CPU::new_from_code("nop +0\nacc 1x\n").unwrap_err(),
+            ParseProgramError {
+                line: 1,
+                text: "acc 1x".to_string()
+            }
+        );
+        assert_eq!(
+            CPU::new_from_code("jmp +1").unwrap().instructions(),
+            &[Operation::Jump(1)]
+        );
+    }
+
+    #[test]
+    fn test_accumulator_wraps() {
+        let code = format!("acc +{}\nacc +1", i32::MAX);
+        let mut cpu = CPU::new_from_code(&code).unwrap();
+        assert_eq!(cpu.run(), AbortState::AbortByEnd);
+        assert_eq!(cpu.accumulator(), i32::MIN);
+    }
+
+    #[test]
+    fn test_invalid_jump() {
+        let mut cpu = CPU::new_from_code("acc +2\njmp -5").unwrap();
+        assert_eq!(cpu.run(), AbortState::AbortByInvalidJump(-4));
+        assert_eq!(cpu.program_counter(), 1);
+        let mut cpu = CPU::new_from_code("jmp +3\nacc +1").unwrap();
+        assert_eq!(cpu.run(), AbortState::AbortByInvalidJump(3));
+        let mut cpu = CPU::new_from_code("jmp +2\nacc +1").unwrap();
+        assert_eq!(cpu.run(), AbortState::AbortByEnd);
+        assert_eq!(cpu.run(), AbortState::AbortByEnd);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let mut cpu = CPU::new_from_code("nop +0\njmp +2\njmp -2\njmp +1\njmp -9").unwrap();
+        cpu.add_breakpoint(2);
+        assert_eq!(
+            cpu.disassemble(),
+            concat!(
+                " 0  nop +0\n",
+                " 1  jmp +2    -> 3\n",
+                "*2  jmp -2    -> 0\n",
+                " 3  jmp +1    -> 4\n",
+                " 4  jmp -9    -> out of range\n",
+            )
+        );
+        assert_eq!(
+            CPU::new_from_code("acc +1\njmp +1").unwrap().disassemble(),
+            " 0  acc +1\n 1  jmp +1    -> end\n"
+        );
+    }
+
+    #[test]
+    fn test_repair() {
+        let cpu = CPU::new_from_code(EXAMPLE).unwrap();
+        let expected = Repair {
+            index: 7,
+            accumulator: 8,
+        };
+        assert_eq!(cpu.repair(), Some(expected));
+        assert_eq!(repair_by_trying(&cpu), Some(expected));
+        // nothing to repair
+        assert_eq!(CPU::new_from_code("acc +1\nnop +0").unwrap().repair(), None);
+        assert_eq!(CPU::new_from_code("jmp +0\njmp -1").unwrap().repair(), None);
+        assert_eq!(
+            CPU::new_from_code("acc +1\njmp -1").unwrap().repair(),
+            Some(Repair {
+                index: 1,
+                accumulator: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_repair_generated() {
+        // random programs, keeping the ones a single flip fixes
+        let mut random = Random::new(5);
+        let mut random = move |n| random.below(n);
+        let mut repaired = 0;
+        for _ in 0..300 {
+            let len = random(30) + 2;
+            let instructions = (0..len)
+                .map(|_| {
+                    let argument = random(9) as i32 - 4;
+                    match random(3) {
+                        0 => Operation::Noop(argument),
+                        1 => Operation::Accumulator(argument),
+                        _ => Operation::Jump(argument),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let cpu = CPU::new(instructions);
+            if cpu.clone().run() != AbortState::AbortByLoop {
+                continue;
+            }
+            // several flips may work, but then both find one
+            let found = cpu.repair();
+            assert_eq!(found.is_some(), repair_by_trying(&cpu).is_some());
+            if let Some(repair) = found {
+                let mut fixed = cpu.clone();
+                fixed.flip_inst(repair.index);
+                assert_eq!(fixed.run(), AbortState::AbortByEnd);
+                repaired += 1;
+            }
+        }
+        assert!(repaired > 10);
+    }
+}
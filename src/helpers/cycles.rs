@@ -0,0 +1,169 @@
+// Cycle detection over iterated functions x_{i+1} = f(x_i).
+// https://en.wikipedia.org/wiki/Cycle_detection
+
+// The sequence enters a cycle after `start` steps (mu) and repeats every
+// `length` steps (lambda), so x_i == x_{i + length} for all i >= start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // maps a step count onto the smallest equivalent one inside the cycle
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+fn step_n<S, F: Fn(&S) -> S>(mut state: S, f: &F, n: usize) -> S {
+    for _ in 0..n {
+        state = f(&state);
+    }
+    state
+}
+
+// Floyd's tortoise and hare. Needs to keep two states alive and evaluates f
+// roughly three times per step, but is the textbook variant.
+pub fn floyd<S: Clone + PartialEq, F: Fn(&S) -> S>(initial: S, f: F) -> Cycle {
+    let mut tortoise = f(&initial);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&f(&hare));
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+// Brent's algorithm: finds the cycle length directly with fewer calls to f.
+pub fn brent<S: Clone + PartialEq, F: Fn(&S) -> S>(initial: S, f: F) -> Cycle {
+    brent_bounded(initial, &f, usize::MAX).unwrap()
+}
+
+// Same as `brent`, but gives up after `limit` applications of f in the search
+// phase. Used to avoid searching forever on sequences without a (short) cycle.
+fn brent_bounded<S: Clone + PartialEq, F: Fn(&S) -> S>(
+    initial: S,
+    f: &F,
+    limit: usize,
+) -> Option<Cycle> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut steps = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = f(&initial);
+    while tortoise != hare {
+        if steps >= limit {
+            return None;
+        }
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = f(&hare);
+        length += 1;
+        steps += 1;
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = step_n(initial, f, length);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+// Applies f until the state does not change anymore.
+pub fn fixed_point<S: PartialEq, F: Fn(&S) -> S>(mut state: S, f: F) -> S {
+    loop {
+        let next = f(&state);
+        if next == state {
+            return state;
+        }
+        state = next;
+    }
+}
+
+// Returns the state after applying f `n` times. If the sequence runs into a
+// cycle before that, the remaining steps are skipped by jumping ahead
+// within the cycle.
+pub fn nth_state<S: Clone + PartialEq, F: Fn(&S) -> S>(state: S, f: F, n: usize) -> S {
+    match brent_bounded(state.clone(), &f, n) {
+        Some(cycle) => step_n(state, &f, cycle.reduce(n)),
+        None => step_n(state, &f, n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(start: usize, length: usize) -> Cycle {
+        Cycle { start, length }
+    }
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn rho(x: &u32) -> u32 {
+        if *x < 5 {
+            x + 1
+        } else {
+            2
+        }
+    }
+
+    #[test]
+    fn test_floyd() {
+        assert_eq!(floyd(0, rho), cycle(2, 4));
+        assert_eq!(floyd(3, rho), cycle(0, 4));
+        assert_eq!(floyd(7, |x| x * 2 % 11), cycle(0, 10));
+    }
+
+    #[test]
+    fn test_brent() {
+        assert_eq!(brent(0, rho), cycle(2, 4));
+        assert_eq!(brent(3, rho), cycle(0, 4));
+        assert_eq!(brent(7, |x| x * 2 % 11), cycle(0, 10));
+        assert_eq!(brent(5, |_| 5), cycle(0, 1));
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        assert_eq!(fixed_point(100u32, |x| x / 2 + 1), 2);
+        assert_eq!(fixed_point(vec![3, 1, 2], |v| v.clone()), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_nth_state() {
+        assert_eq!(nth_state(0, rho, 0), 0);
+        assert_eq!(nth_state(0, rho, 1), 1);
+        assert_eq!(nth_state(0, rho, 6), 2);
+        assert_eq!(nth_state(0, rho, 1_000_000_000_000), 4);
+        // sequence without a cycle within reach is simply stepped through
+        assert_eq!(nth_state(0u64, |x| x + 1, 1000), 1000);
+    }
+}
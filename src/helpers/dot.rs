@@ -0,0 +1,148 @@
+use std::fmt;
+use std::hash::Hash;
+
+use super::graph::Graph;
+
+// Writer for Graphviz DOT files, to look at parsed puzzle structures with
+// `dot -Tsvg`. Ids and labels are quoted, so any Display value works.
+// https://graphviz.org/doc/info/lang.html
+
+#[derive(Debug, Clone)]
+pub struct Dot {
+    name: String,
+    statements: Vec<String>,
+}
+
+// a quoted DOT string
+pub fn quote(s: &str) -> String {
+    let mut result = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn attribute_list(attributes: &[(&str, &str)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let list = attributes
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, quote(v)))
+        .collect::<Vec<_>>();
+    format!(" [{}]", list.join(", "))
+}
+
+impl Dot {
+    pub fn digraph(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            statements: vec![],
+        }
+    }
+
+    pub fn node(&mut self, id: impl fmt::Display, label: impl fmt::Display) -> &mut Self {
+        self.node_with(id, &[("label", &label.to_string())])
+    }
+
+    // a node with any attributes, e.g. ("shape", "box")
+    pub fn node_with(&mut self, id: impl fmt::Display, attributes: &[(&str, &str)]) -> &mut Self {
+        self.statements.push(format!(
+            "{}{};",
+            quote(&id.to_string()),
+            attribute_list(attributes)
+        ));
+        self
+    }
+
+    pub fn edge(&mut self, from: impl fmt::Display, to: impl fmt::Display) -> &mut Self {
+        self.edge_with(from, to, &[])
+    }
+
+    pub fn labelled_edge(
+        &mut self,
+        from: impl fmt::Display,
+        to: impl fmt::Display,
+        label: impl fmt::Display,
+    ) -> &mut Self {
+        self.edge_with(from, to, &[("label", &label.to_string())])
+    }
+
+    pub fn edge_with(
+        &mut self,
+        from: impl fmt::Display,
+        to: impl fmt::Display,
+        attributes: &[(&str, &str)],
+    ) -> &mut Self {
+        self.statements.push(format!(
+            "{} -> {}{};",
+            quote(&from.to_string()),
+            quote(&to.to_string()),
+            attribute_list(attributes)
+        ));
+        self
+    }
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph {} {{", quote(&self.name))?;
+        for statement in &self.statements {
+            writeln!(f, "    {}", statement)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl<K: Clone + Eq + Hash + fmt::Display, W: Clone + fmt::Display> Graph<K, W> {
+    // every node and every edge labelled with its weight
+    pub fn to_dot(&self, name: &str) -> Dot {
+        let mut dot = Dot::digraph(name);
+        for node in self.nodes() {
+            dot.node(node, node);
+        }
+        for (from, to, w) in self.edges() {
+            dot.labelled_edge(from, to, w);
+        }
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer() {
+        let mut dot = Dot::digraph("test");
+        dot.node(1, "say \"hi\"")
+            .node_with(2, &[("shape", "box"), ("label", "a\\b")])
+            .edge(1, 2)
+            .labelled_edge(2, 1, "two\nlines");
+        assert_eq!(
+            dot.to_string(),
+            "\
+digraph \"test\" {
+    \"1\" [label=\"say \\\"hi\\\"\"];
+    \"2\" [shape=\"box\", label=\"a\\\\b\"];
+    \"1\" -> \"2\";
+    \"2\" -> \"1\" [label=\"two\\nlines\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_graph_to_dot() {
+        let graph: Graph<&str, u32> = [("a", "b", 3)].into_iter().collect();
+        let dot = graph.to_dot("g").to_string();
+        assert!(dot.contains("\"a\" [label=\"a\"];"));
+        assert!(dot.contains("\"a\" -> \"b\" [label=\"3\"];"));
+    }
+}
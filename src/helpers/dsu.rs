@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Disjoint set union (union-find): keeps track of which elements belong
+// together while sets are merged. With union by rank and path compression
+// every operation takes nearly constant amortized time.
+// https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+
+#[derive(Debug, Clone, Default)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    // only up to date for roots
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl Dsu {
+    // n elements 0..n, each in a set of its own
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // adds a new element in a set of its own, returns its index
+    pub fn add(&mut self) -> usize {
+        let x = self.parent.len();
+        self.parent.push(x);
+        self.rank.push(0);
+        self.size.push(1);
+        self.components += 1;
+        x
+    }
+
+    // the representative of the set x is in
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // point everything on the way directly at the root
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    // Merges the sets of a and b, returns false if they already were the
+    // same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut a = self.find(a);
+        let mut b = self.find(b);
+        if a == b {
+            return false;
+        }
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // number of disjoint sets
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    // number of elements in the set x is in
+    pub fn component_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    // The members of every set, each sorted, ordered by their smallest
+    // member.
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut index_of_root = HashMap::new();
+        let mut result: Vec<Vec<usize>> = vec![];
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let i = *index_of_root.entry(root).or_insert_with(|| {
+                result.push(vec![]);
+                result.len() - 1
+            });
+            result[i].push(x);
+        }
+        result
+    }
+}
+
+// Union-find over arbitrary keys such as points or names. Keys are added the
+// first time they are mentioned.
+#[derive(Debug, Clone)]
+pub struct KeyedDsu<K> {
+    indices: HashMap<K, usize>,
+    keys: Vec<K>,
+    dsu: Dsu,
+}
+
+impl<K> Default for KeyedDsu<K> {
+    fn default() -> Self {
+        Self {
+            indices: HashMap::new(),
+            keys: vec![],
+            dsu: Dsu::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedDsu<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    // adds the key in a set of its own if it is new, returns its index
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(i) = self.indices.get(&key) {
+            return *i;
+        }
+        let i = self.dsu.add();
+        self.keys.push(key.clone());
+        self.indices.insert(key, i);
+        i
+    }
+
+    // the representative of the set of key, None for unknown keys
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let i = *self.indices.get(key)?;
+        let root = self.dsu.find(i);
+        Some(&self.keys[root])
+    }
+
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let a = self.insert(a);
+        let b = self.insert(b);
+        self.dsu.union(a, b)
+    }
+
+    // false if either key is unknown
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.indices.get(a), self.indices.get(b)) {
+            (Some(a), Some(b)) => self.dsu.same(*a, *b),
+            _ => false,
+        }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.dsu.component_count()
+    }
+
+    pub fn component_size(&mut self, key: &K) -> Option<usize> {
+        let i = *self.indices.get(key)?;
+        Some(self.dsu.component_size(i))
+    }
+
+    // the members of every set, in the order the keys were added
+    pub fn sets(&mut self) -> Vec<Vec<K>> {
+        self.dsu
+            .sets()
+            .into_iter()
+            .map(|set| set.into_iter().map(|i| self.keys[i].clone()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use super::*;
+    use crate::algebra_helpers::{Point2, PointGrid};
+    use crate::helpers::random::Random;
+    use test::Bencher;
+
+    #[test]
+    fn test_union_find() {
+        let mut dsu = Dsu::new(6);
+        assert_eq!(dsu.component_count(), 6);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(4, 1));
+        assert!(dsu.union(2, 3));
+        assert!(!dsu.union(0, 4));
+        assert_eq!(dsu.component_count(), 3);
+        assert!(dsu.same(0, 4));
+        assert!(!dsu.same(0, 2));
+        assert_eq!(dsu.component_size(4), 3);
+        assert_eq!(dsu.component_size(5), 1);
+        assert_eq!(dsu.sets(), vec![vec![0, 1, 4], vec![2, 3], vec![5]]);
+
+        let x = dsu.add();
+        assert_eq!(x, 6);
+        assert!(dsu.union(x, 3));
+        assert_eq!(dsu.component_size(2), 3);
+        assert_eq!(dsu.component_count(), 3);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // path compression keeps the tree flat, whatever the union order
+        let n = 100_000;
+        let mut dsu = Dsu::new(n);
+        for i in 1..n {
+            dsu.union(i - 1, i);
+        }
+        assert_eq!(dsu.component_count(), 1);
+        assert_eq!(dsu.component_size(0), n);
+        let root = dsu.find(n - 1);
+        assert!((0..n).all(|i| dsu.find(i) == root));
+    }
+
+    #[test]
+    fn test_keyed() {
+        let mut names = KeyedDsu::new();
+        names.union(String::from("fish"), String::from("dairy"));
+        names.union(String::from("nuts"), String::from("soy"));
+        names.union(String::from("dairy"), String::from("soy"));
+        names.insert(String::from("eggs"));
+        assert_eq!(names.len(), 5);
+        assert_eq!(names.component_count(), 2);
+        assert!(names.same(&String::from("fish"), &String::from("nuts")));
+        assert!(!names.same(&String::from("fish"), &String::from("wheat")));
+        assert_eq!(names.component_size(&String::from("soy")), Some(4));
+        assert_eq!(names.component_size(&String::from("wheat")), None);
+        assert_eq!(
+            names.sets(),
+            vec![vec!["fish", "dairy", "nuts", "soy"], vec!["eggs"]]
+        );
+    }
+
+    #[test]
+    fn test_keyed_points() {
+        // orthogonally connected islands, as a region labelling would find
+        let grid =
+            PointGrid::parse_slice_with("#.#\n#..\n.##", Point2::zero(), &['.'], Some).unwrap();
+        let mut islands = KeyedDsu::new();
+        for p in grid.keys() {
+            islands.insert(*p);
+            for d in Point2::directions() {
+                if grid.contains(&(*p + d)) {
+                    islands.union(*p, *p + d);
+                }
+            }
+        }
+        assert_eq!(islands.component_count(), 3);
+        assert_eq!(islands.component_size(&Point2::new(1, 2)), Some(2));
+        let root = *islands.find(&Point2::new(0, 0)).unwrap();
+        assert_eq!(islands.find(&Point2::new(0, 1)), Some(&root));
+    }
+
+    #[bench]
+    fn bench_random_unions(b: &mut Bencher) {
+        let n = 100_000;
+        let mut random = Random::new(0x2545_f491_4f6c_dd1d);
+        let pairs = (0..n)
+            .map(|_| {
+                let r = random.next_u64();
+                ((r % n as u64) as usize, (r >> 32) as usize % n)
+            })
+            .collect::<Vec<_>>();
+        b.iter(|| {
+            let mut dsu = Dsu::new(n);
+            for (a, b) in &pairs {
+                dsu.union(*a, *b);
+            }
+            dsu.component_count()
+        });
+    }
+}
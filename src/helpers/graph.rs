@@ -0,0 +1,566 @@
+use num_traits::Zero;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+use crate::algebra_helpers::{Point, PointGrid};
+
+// Searches over directed graphs. Everything that can list the successors of
+// a node gets the searches through `Traversable`: the adjacency list `Graph`
+// for puzzles that spell out their edges (bag rules), `GridGraph` for mazes
+// stored in a `PointGrid`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<K> {
+    // the nodes on the cycle, each has an edge to the next and the last one
+    // back to the first
+    pub cycle: Vec<K>,
+}
+
+impl<K: fmt::Debug> fmt::Display for CycleError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph has a cycle: {:?}", self.cycle)
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for CycleError<K> {}
+
+// a cheapest path, including the start and the goal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<K, W> {
+    pub cost: W,
+    pub nodes: Vec<K>,
+}
+
+pub trait Traversable {
+    type Node: Clone + Eq + Hash;
+    type Weight: Clone;
+
+    fn successors(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)>;
+
+    // every node reachable from start, in the order they are found
+    fn bfs(&self, start: &Self::Node) -> Vec<Self::Node> {
+        self.bfs_distances(start)
+            .into_iter()
+            .map(|(node, _)| node)
+            .collect()
+    }
+
+    // the reachable nodes with the number of edges to get there, ordered by
+    // that distance
+    fn bfs_distances(&self, start: &Self::Node) -> Vec<(Self::Node, usize)> {
+        let mut seen = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(start.clone(), 0)]);
+        let mut result = vec![];
+        while let Some((node, distance)) = queue.pop_front() {
+            for (next, _) in self.successors(&node) {
+                if seen.insert(next.clone()) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+            result.push((node, distance));
+        }
+        result
+    }
+
+    // every node reachable from start in depth first preorder
+    fn dfs(&self, start: &Self::Node) -> Vec<Self::Node> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![start.clone()];
+        let mut result = vec![];
+        while let Some(node) = stack.pop() {
+            if !seen.insert(node.clone()) {
+                continue;
+            }
+            let successors = self.successors(&node);
+            stack.extend(successors.into_iter().rev().map(|(next, _)| next));
+            result.push(node);
+        }
+        result
+    }
+
+    fn reachable(&self, start: &Self::Node) -> HashSet<Self::Node> {
+        self.bfs(start).into_iter().collect()
+    }
+
+    // The nodes reachable from start, each one after all of its successors.
+    fn post_order(&self, start: &Self::Node) -> Result<Vec<Self::Node>, CycleError<Self::Node>> {
+        post_order_from(self, [start.clone()])
+    }
+
+    // Computes a value for every node reachable from start out of the values
+    // of its successors (with the edge weights), leaves first, and returns
+    // the one for start. Each node is only computed once, no matter how many
+    // paths lead to it.
+    fn fold_dag<V: Clone>(
+        &self,
+        start: &Self::Node,
+        mut f: impl FnMut(&Self::Node, &[(Self::Weight, V)]) -> V,
+    ) -> Result<V, CycleError<Self::Node>> {
+        let mut values: HashMap<Self::Node, V> = HashMap::new();
+        for node in self.post_order(start)? {
+            let inputs = self
+                .successors(&node)
+                .into_iter()
+                .map(|(next, w)| (w, values[&next].clone()))
+                .collect::<Vec<_>>();
+            let value = f(&node, &inputs);
+            values.insert(node, value);
+        }
+        Ok(values.remove(start).unwrap())
+    }
+
+    // cheapest path to the first goal, weights must not be negative
+    fn dijkstra(
+        &self,
+        start: &Self::Node,
+        is_goal: impl Fn(&Self::Node) -> bool,
+    ) -> Option<Path<Self::Node, Self::Weight>>
+    where
+        Self::Weight: Copy + Ord + Zero,
+    {
+        self.astar(start, is_goal, |_| Self::Weight::zero())
+    }
+
+    // the cost of the cheapest path to every reachable node
+    fn dijkstra_all(&self, start: &Self::Node) -> HashMap<Self::Node, Self::Weight>
+    where
+        Self::Weight: Copy + Ord + Zero,
+    {
+        let mut costs = HashMap::new();
+        search(self, start, |_| false, |_| Self::Weight::zero(), &mut costs);
+        costs.into_iter().map(|(k, (cost, _))| (k, cost)).collect()
+    }
+
+    // Like dijkstra, but explores nodes that look closer to a goal first.
+    // The heuristic must never overestimate the remaining cost. It doesn't
+    // have to be consistent, nodes are expanded again when a cheaper path to
+    // them turns up.
+    fn astar(
+        &self,
+        start: &Self::Node,
+        is_goal: impl Fn(&Self::Node) -> bool,
+        heuristic: impl Fn(&Self::Node) -> Self::Weight,
+    ) -> Option<Path<Self::Node, Self::Weight>>
+    where
+        Self::Weight: Copy + Ord + Zero,
+    {
+        let mut costs = HashMap::new();
+        let goal = search(self, start, is_goal, heuristic, &mut costs)?;
+        let mut nodes = vec![goal.clone()];
+        while let Some((_, Some(previous))) = costs.get(nodes.last().unwrap()) {
+            nodes.push(previous.clone());
+        }
+        nodes.reverse();
+        Some(Path {
+            cost: costs[&goal].0,
+            nodes,
+        })
+    }
+}
+
+// Depth first search from every root that wasn't reached yet, with an
+// explicit stack so long chains don't overflow it.
+fn post_order_from<G: Traversable + ?Sized>(
+    graph: &G,
+    roots: impl IntoIterator<Item = G::Node>,
+) -> Result<Vec<G::Node>, CycleError<G::Node>> {
+    // false while the node is still on the stack
+    let mut finished: HashMap<G::Node, bool> = HashMap::new();
+    let mut result = vec![];
+    for root in roots {
+        if finished.contains_key(&root) {
+            continue;
+        }
+        finished.insert(root.clone(), false);
+        let mut stack = vec![(root.clone(), graph.successors(&root), 0)];
+        while let Some((node, successors, index)) = stack.last_mut() {
+            if let Some((next, _)) = successors.get(*index) {
+                *index += 1;
+                let next = next.clone();
+                match finished.get(&next) {
+                    None => {
+                        finished.insert(next.clone(), false);
+                        let successors = graph.successors(&next);
+                        stack.push((next, successors, 0));
+                    }
+                    Some(false) => {
+                        let position = stack.iter().position(|(n, _, _)| *n == next).unwrap();
+                        let cycle = stack[position..].iter().map(|(n, _, _)| n.clone());
+                        return Err(CycleError {
+                            cycle: cycle.collect(),
+                        });
+                    }
+                    Some(true) => {}
+                }
+            } else {
+                finished.insert(node.clone(), true);
+                result.push(node.clone());
+                stack.pop();
+            }
+        }
+    }
+    Ok(result)
+}
+
+struct Entry<K, W> {
+    priority: W,
+    cost: W,
+    node: K,
+}
+
+// reversed, so the binary heap pops the lowest priority first
+impl<K, W: Ord> Ord for Entry<K, W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<K, W: Ord> PartialOrd for Entry<K, W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, W: Ord> PartialEq for Entry<K, W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<K, W: Ord> Eq for Entry<K, W> {}
+
+type Predecessors<K, W> = HashMap<K, (W, Option<K>)>;
+
+// Fills in the best known cost and predecessor of every node it visits,
+// returns the goal it stopped at.
+fn search<G: Traversable + ?Sized>(
+    graph: &G,
+    start: &G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+    heuristic: impl Fn(&G::Node) -> G::Weight,
+    costs: &mut Predecessors<G::Node, G::Weight>,
+) -> Option<G::Node>
+where
+    G::Weight: Copy + Ord + Zero,
+{
+    let mut heap = BinaryHeap::new();
+    costs.insert(start.clone(), (G::Weight::zero(), None));
+    heap.push(Entry {
+        priority: heuristic(start),
+        cost: G::Weight::zero(),
+        node: start.clone(),
+    });
+    while let Some(Entry { cost, node, .. }) = heap.pop() {
+        // stale entry, a cheaper path has been found since
+        if costs[&node].0 < cost {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some(node);
+        }
+        for (next, weight) in graph.successors(&node) {
+            let next_cost = cost + weight;
+            if costs.get(&next).is_some_and(|(c, _)| *c <= next_cost) {
+                continue;
+            }
+            costs.insert(next.clone(), (next_cost, Some(node.clone())));
+            heap.push(Entry {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+    None
+}
+
+// Adjacency list. Nodes keep the order they were first mentioned in, so
+// searches and the topological order don't depend on hashing.
+#[derive(Debug, Clone)]
+pub struct Graph<K, W = ()> {
+    nodes: Vec<K>,
+    indices: HashMap<K, usize>,
+    edges: Vec<Vec<(usize, W)>>,
+}
+
+impl<K, W> Default for Graph<K, W> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            indices: HashMap::new(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, W: Clone> Graph<K, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn index(&mut self, node: K) -> usize {
+        if let Some(i) = self.indices.get(&node) {
+            return *i;
+        }
+        self.nodes.push(node.clone());
+        self.edges.push(vec![]);
+        self.indices.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    // adds a node without edges, nothing happens if it already exists
+    pub fn add_node(&mut self, node: K) {
+        self.index(node);
+    }
+
+    pub fn add_edge(&mut self, from: K, to: K, weight: W) {
+        let from = self.index(from);
+        let to = self.index(to);
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, node: &K) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn nodes(&self) -> &[K] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&K, &K, &W)> {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges
+                    .iter()
+                    .map(move |(to, w)| (&self.nodes[from], &self.nodes[*to], w))
+            })
+    }
+
+    // the same graph with every edge pointing the other way
+    pub fn reversed(&self) -> Self {
+        let mut result = Self::new();
+        for node in &self.nodes {
+            result.add_node(node.clone());
+        }
+        for (from, to, w) in self.edges() {
+            result.add_edge(to.clone(), from.clone(), w.clone());
+        }
+        result
+    }
+
+    // Every node before all nodes it has an edge to.
+    pub fn topological_order(&self) -> Result<Vec<K>, CycleError<K>> {
+        let mut result = post_order_from(self, self.nodes.iter().cloned())?;
+        result.reverse();
+        Ok(result)
+    }
+}
+
+impl<K: Clone + Eq + Hash, W: Clone> FromIterator<(K, K, W)> for Graph<K, W> {
+    fn from_iter<I: IntoIterator<Item = (K, K, W)>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for (from, to, w) in iter {
+            result.add_edge(from, to, w);
+        }
+        result
+    }
+}
+
+impl<K: Clone + Eq + Hash, W: Clone> Traversable for Graph<K, W> {
+    type Node = K;
+    type Weight = W;
+
+    fn successors(&self, node: &K) -> Vec<(K, W)> {
+        self.indices.get(node).map_or(vec![], |i| {
+            self.edges[*i]
+                .iter()
+                .map(|(to, w)| (self.nodes[*to].clone(), w.clone()))
+                .collect()
+        })
+    }
+}
+
+// A point grid seen as a graph: every cell the predicate lets through is a
+// node, with edges of weight 1 to the passable cells in the given directions.
+pub struct GridGraph<'a, U, const N: usize, P> {
+    grid: &'a PointGrid<isize, N, U>,
+    directions: Vec<Point<isize, N>>,
+    passable: P,
+}
+
+impl<'a, U, const N: usize, P: Fn(&U) -> bool> GridGraph<'a, U, N, P> {
+    pub fn new(
+        grid: &'a PointGrid<isize, N, U>,
+        directions: Vec<Point<isize, N>>,
+        passable: P,
+    ) -> Self {
+        Self {
+            grid,
+            directions,
+            passable,
+        }
+    }
+}
+
+impl<U, const N: usize, P: Fn(&U) -> bool> Traversable for GridGraph<'_, U, N, P> {
+    type Node = Point<isize, N>;
+    type Weight = usize;
+
+    fn successors(&self, node: &Self::Node) -> Vec<(Self::Node, usize)> {
+        self.directions
+            .iter()
+            .map(|d| *node + *d)
+            .filter(|p| self.grid.get(p).is_some_and(|u| (self.passable)(u)))
+            .map(|p| (p, 1))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::Point2;
+
+    //   a -> b -> d
+    //   |         ^
+    //   +--> c ---+    e (alone)
+    fn diamond() -> Graph<char, u32> {
+        let mut graph: Graph<char, u32> =
+            [('a', 'b', 1), ('a', 'c', 4), ('b', 'd', 5), ('c', 'd', 1)]
+                .into_iter()
+                .collect();
+        graph.add_node('e');
+        graph
+    }
+
+    #[test]
+    fn test_reachability() {
+        let graph = diamond();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.bfs(&'a'), vec!['a', 'b', 'c', 'd']);
+        assert_eq!(graph.dfs(&'a'), vec!['a', 'b', 'd', 'c']);
+        assert_eq!(graph.bfs_distances(&'a').last(), Some(&('d', 2)));
+        assert_eq!(graph.reachable(&'e'), HashSet::from(['e']));
+        assert_eq!(graph.reversed().reachable(&'d').len(), 4);
+        assert_eq!(graph.bfs(&'x'), vec!['x']);
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph = diamond();
+        let order = graph.topological_order().unwrap();
+        assert_eq!(order.len(), 5);
+        for (from, to, _) in graph.edges() {
+            let position = |n| order.iter().position(|o| o == n).unwrap();
+            assert!(position(from) < position(to));
+        }
+
+        let mut cyclic = graph.clone();
+        cyclic.add_edge('d', 'b', 1);
+        let error = cyclic.topological_order().unwrap_err();
+        assert_eq!(error.cycle, vec!['b', 'd']);
+        assert!(cyclic.post_order(&'e').is_ok());
+        assert!(cyclic.fold_dag(&'c', |_, _: &[(u32, ())]| ()).is_err());
+    }
+
+    #[test]
+    fn test_fold_dag() {
+        // number of paths to d, counted once per node
+        let mut calls = 0;
+        let paths = diamond()
+            .fold_dag(&'a', |node, successors: &[(u32, u64)]| {
+                calls += 1;
+                if *node == 'd' {
+                    1
+                } else {
+                    successors.iter().map(|(_, v)| v).sum()
+                }
+            })
+            .unwrap();
+        assert_eq!(paths, 2);
+        assert_eq!(calls, 4);
+
+        // weighted: the sum over all paths of the product of the weights
+        let weighted = diamond()
+            .fold_dag(&'a', |_, successors: &[(u32, u32)]| {
+                1 + successors.iter().map(|(w, v)| w * v).sum::<u32>()
+            })
+            .unwrap();
+        assert_eq!(weighted, 1 + (1 + 5) + 4 * (1 + 1));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let graph = diamond();
+        let path = graph.dijkstra(&'a', |n| *n == 'd').unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.nodes, vec!['a', 'c', 'd']);
+        assert_eq!(graph.dijkstra(&'a', |n| *n == 'e'), None);
+        assert_eq!(
+            graph.dijkstra_all(&'a'),
+            HashMap::from([('a', 0), ('b', 1), ('c', 4), ('d', 5)])
+        );
+    }
+
+    #[test]
+    fn test_grid_graph() {
+        let grid = PointGrid::parse_with(
+            "\
+S.#.....
+.##.###.
+....#..G
+###...#.",
+            Some,
+        )
+        .unwrap();
+        let graph = GridGraph::new(&grid, Point2::directions(), |c: &char| *c != '#');
+        let start = Point2::new(0, 0);
+        let goal = Point2::new(7, 2);
+
+        let by_bfs = graph
+            .bfs_distances(&start)
+            .into_iter()
+            .find(|(p, _)| *p == goal)
+            .unwrap();
+        let path = graph.dijkstra(&start, |p| *p == goal).unwrap();
+        let heuristic = |p: &Point2<isize>| p.distance_manhattan_from(goal) as usize;
+        let guided = graph.astar(&start, |p| *p == goal, heuristic).unwrap();
+        // around the bottom, not through the top row
+        assert_eq!(by_bfs.1, 11);
+        assert_eq!(path.cost, 11);
+        assert_eq!(guided.cost, 11);
+        assert_eq!(guided.nodes.len(), 12);
+        assert!(guided
+            .nodes
+            .windows(2)
+            .all(|w| w[0].distance_manhattan_from(w[1]) == 1));
+        assert_eq!(graph.reachable(&start).len(), 21);
+    }
+
+    #[test]
+    fn test_astar_inconsistent_heuristic() {
+        let mut graph = Graph::new();
+        graph.add_edge('s', 'a', 4);
+        graph.add_edge('s', 'b', 1);
+        graph.add_edge('b', 'a', 1);
+        graph.add_edge('a', 'g', 5);
+        // admissible, but b looks worse than it is, so a is first expanded
+        // over the expensive direct edge
+        let heuristic = |n: &char| if *n == 'b' { 5 } else { 0 };
+        let path = graph.astar(&'s', |n| *n == 'g', heuristic).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.nodes, vec!['s', 'b', 'a', 'g']);
+    }
+}
@@ -0,0 +1,267 @@
+use num_traits::Zero;
+use std::fmt;
+use std::ops::{Range, Sub};
+
+// A set of values stored as sorted, disjoint and non-adjacent half-open
+// intervals [start, end). Membership queries are a binary search.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: vec![] }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // sorts and merges overlapping or touching intervals, drops empty ones
+    fn normalized(mut intervals: Vec<(T, T)>) -> Self {
+        intervals.retain(|(s, e)| s < e);
+        intervals.sort_unstable();
+
+        let mut result: Vec<(T, T)> = Vec::with_capacity(intervals.len());
+        for (start, end) in intervals {
+            match result.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => result.push((start, end)),
+            }
+        }
+        Self { intervals: result }
+    }
+
+    // merges with the intervals it overlaps or touches, found by binary search
+    pub fn insert(&mut self, range: Range<T>) {
+        let (start, end) = (range.start, range.end);
+        if start >= end {
+            return;
+        }
+        let first = self.intervals.partition_point(|(_, e)| *e < start);
+        let last = self.intervals.partition_point(|(s, _)| *s <= end);
+        if first == last {
+            self.intervals.insert(first, (start, end));
+        } else {
+            let merged = (
+                start.min(self.intervals[first].0),
+                end.max(self.intervals[last - 1].1),
+            );
+            self.intervals.splice(first..last, [merged]);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // number of disjoint intervals, not the number of contained values
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.intervals.iter().map(|(s, e)| *s..*e)
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.0)
+    }
+
+    // exclusive upper bound
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|i| i.1)
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let idx = self.intervals.partition_point(|(_, e)| e <= value);
+        self.intervals.get(idx).is_some_and(|(s, _)| s <= value)
+    }
+
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        if range.start >= range.end {
+            return true;
+        }
+        let idx = self.intervals.partition_point(|(_, e)| *e <= range.start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|(s, e)| *s <= range.start && range.end <= *e)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.iter().all(|r| other.contains_range(&r))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(
+            self.intervals
+                .iter()
+                .chain(other.intervals.iter())
+                .copied()
+                .collect(),
+        )
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            let (start, end) = (a.0.max(b.0), a.1.min(b.1));
+            if start < end {
+                result.push((start, end));
+            }
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals: result }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = vec![];
+        let mut j = 0;
+        for &(start, end) in &self.intervals {
+            let mut current = start;
+            // skip everything in other that ends before this interval
+            while j < other.intervals.len() && other.intervals[j].1 <= current {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].0 < end {
+                let (os, oe) = other.intervals[k];
+                if os > current {
+                    result.push((current, os));
+                }
+                current = current.max(oe);
+                k += 1;
+            }
+            if current < end {
+                result.push((current, end));
+            }
+        }
+        Self { intervals: result }
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Zero> IntervalSet<T> {
+    // amount of values contained in the set
+    pub fn total_length(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |acc, (s, e)| acc + (*e - *s))
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(value: Range<T>) -> Self {
+        Self::normalized(vec![(value.start, value.end)])
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalized(iter.into_iter().map(|r| (r.start, r.end)).collect())
+    }
+}
+
+impl<T: Copy + Ord> Extend<Range<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.extend(iter.into_iter().map(|r| (r.start, r.end)));
+        *self = Self::normalized(intervals);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.intervals.iter().map(|(s, e)| s..e))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::random::Random;
+
+    fn set(ranges: &[Range<i32>]) -> IntervalSet<i32> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_normalization() {
+        let s = set(&[5..7, 1..3, 2..4, 7..9, 10..10]);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![1..4, 5..9]);
+        assert_eq!(s.interval_count(), 2);
+        assert_eq!(s.total_length(), 7);
+        assert_eq!((s.min(), s.max()), (Some(1), Some(9)));
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut s = set(&[1..3, 5..7, 10..12]);
+        s.insert(3..5);
+        assert_eq!(s, set(&[1..7, 10..12]));
+        s.insert(8..9);
+        s.insert(0..0);
+        assert_eq!(s, set(&[1..7, 8..9, 10..12]));
+        s.insert(0..20);
+        assert_eq!(s, IntervalSet::from(0..20));
+
+        // compare against a plain array of values
+        let mut random = Random::new(5);
+        let mut s = IntervalSet::new();
+        let mut values = [false; 100];
+        for _ in 0..200 {
+            let start = random.below(100) as i32;
+            let end = (start + random.below(8) as i32).min(100);
+            s.insert(start..end);
+            values[start as usize..end as usize].fill(true);
+            assert!((0..100).all(|x| s.contains(&x) == values[x as usize]));
+            assert_eq!(s, s.iter().collect());
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = set(&[1..4, 5..9, 20..21]);
+        let contained = (0..25).filter(|v| s.contains(v)).collect::<Vec<_>>();
+        assert_eq!(contained, vec![1, 2, 3, 5, 6, 7, 8, 20]);
+        assert!(s.contains_range(&(5..9)));
+        assert!(!s.contains_range(&(3..6)));
+        assert!(set(&[2..3, 6..7]).is_subset(&s));
+        assert!(!IntervalSet::from(2..5).is_subset(&s));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = set(&[5..25, 28..40]);
+        assert_eq!(a.union(&b), IntervalSet::from(0..40));
+        assert_eq!(a.intersection(&b), set(&[5..10, 20..25, 28..30]));
+        assert_eq!(a.difference(&b), set(&[0..5, 25..28]));
+        assert_eq!(b.difference(&a), set(&[10..20, 30..40]));
+        assert_eq!(a.difference(&a), IntervalSet::new());
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+
+        // compare against plain sets of values
+        for x in -5..45 {
+            assert_eq!(a.union(&b).contains(&x), a.contains(&x) || b.contains(&x));
+            assert_eq!(
+                a.intersection(&b).contains(&x),
+                a.contains(&x) && b.contains(&x)
+            );
+            assert_eq!(
+                a.difference(&b).contains(&x),
+                a.contains(&x) && !b.contains(&x)
+            );
+        }
+    }
+}
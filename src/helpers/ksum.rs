@@ -0,0 +1,199 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+// Searches for numbers adding up to a target: pairs, any number of elements,
+// pairs within a sliding window and contiguous runs.
+
+// Indices i < j of two numbers adding up to target, the pair that ends first.
+pub fn two_sum(numbers: &[i64], target: i64) -> Option<(usize, usize)> {
+    let mut seen = HashMap::new();
+    for (j, n) in numbers.iter().enumerate() {
+        if let Some(i) = seen.get(&(target - n)) {
+            return Some((*i, j));
+        }
+        seen.entry(*n).or_insert(j);
+    }
+    None
+}
+
+// Indices of k different numbers adding up to target, in increasing order.
+// Sorts once, then fixes all but two numbers and closes in on the last two
+// from both ends, O(n^(k-1)).
+pub fn k_sum(numbers: &[i64], k: usize, target: i64) -> Option<Vec<usize>> {
+    let mut order = (0..numbers.len()).collect::<Vec<_>>();
+    order.sort_by_key(|i| numbers[*i]);
+    let sorted = order.iter().map(|i| numbers[*i]).collect::<Vec<_>>();
+    let mut picked = k_sum_sorted(&sorted, 0, k, target)?
+        .into_iter()
+        .map(|i| order[i])
+        .collect::<Vec<_>>();
+    picked.sort();
+    Some(picked)
+}
+
+// positions in sorted from start on
+fn k_sum_sorted(sorted: &[i64], start: usize, k: usize, target: i64) -> Option<Vec<usize>> {
+    let rest = &sorted[start..];
+    match k {
+        0 => (target == 0).then(Vec::new),
+        1 => rest.binary_search(&target).ok().map(|i| vec![start + i]),
+        2 => {
+            if rest.len() < 2 {
+                return None;
+            }
+            let (mut low, mut high) = (start, sorted.len() - 1);
+            while low < high {
+                match (sorted[low] + sorted[high]).cmp(&target) {
+                    std::cmp::Ordering::Equal => return Some(vec![low, high]),
+                    std::cmp::Ordering::Less => low += 1,
+                    std::cmp::Ordering::Greater => high -= 1,
+                }
+            }
+            None
+        }
+        _ => (start..sorted.len()).find_map(|i| {
+            let mut picked = k_sum_sorted(sorted, i + 1, k - 1, target - sorted[i])?;
+            picked.insert(0, i);
+            Some(picked)
+        }),
+    }
+}
+
+// Whether some two numbers with different values in window add up to target.
+pub fn is_sum_of_two(window: &[i64], target: i64) -> bool {
+    let mut seen = HashSet::new();
+    window.iter().any(|n| {
+        let found = target - n != *n && seen.contains(&(target - n));
+        seen.insert(*n);
+        found
+    })
+}
+
+// The index of the first number after the first `window` ones that isn't
+// the sum of two numbers with different values among the `window` before it.
+// Keeps counts of the window while it slides, O(n * window).
+pub fn first_invalid(numbers: &[i64], window: usize) -> Option<usize> {
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for n in numbers.iter().take(window) {
+        *counts.entry(*n).or_default() += 1;
+    }
+    for i in window..numbers.len() {
+        let target = numbers[i];
+        let valid = numbers[i - window..i]
+            .iter()
+            .any(|x| target - x != *x && counts.contains_key(&(target - x)));
+        if !valid {
+            return Some(i);
+        }
+        let leaving = numbers[i - window];
+        let count = counts.get_mut(&leaving).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&leaving);
+        }
+        *counts.entry(target).or_default() += 1;
+    }
+    None
+}
+
+// The range of at least `min_len` consecutive numbers adding up to target,
+// the one that ends first. Works with negative numbers too, by looking up
+// the prefix sums seen so far.
+pub fn contiguous_sum(numbers: &[i64], target: i64, min_len: usize) -> Option<Range<usize>> {
+    let mut prefix = vec![0];
+    for n in numbers {
+        prefix.push(prefix.last().unwrap() + n);
+    }
+    // earliest start for every prefix sum far enough behind
+    let min_len = min_len.max(1);
+    let mut starts = HashMap::new();
+    for end in min_len..prefix.len() {
+        starts.entry(prefix[end - min_len]).or_insert(end - min_len);
+        if let Some(start) = starts.get(&(prefix[end] - target)) {
+            return Some(*start..end);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::random::Random;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_two_sum() {
+        let numbers = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(two_sum(&numbers, 2020), Some((0, 3)));
+        assert_eq!(two_sum(&numbers, 1345), Some((1, 2)));
+        assert_eq!(two_sum(&numbers, 3442), None);
+        // the same position can't be used twice, the same value can
+        assert_eq!(two_sum(&[5, 3, 5], 10), Some((0, 2)));
+        assert_eq!(two_sum(&[5, 3], 10), None);
+    }
+
+    #[test]
+    fn test_k_sum() {
+        let numbers = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(k_sum(&numbers, 2, 2020), Some(vec![0, 3]));
+        assert_eq!(k_sum(&numbers, 3, 2020), Some(vec![1, 2, 4]));
+        assert_eq!(k_sum(&numbers, 1, 366), Some(vec![2]));
+        assert_eq!(k_sum(&numbers, 0, 0), Some(vec![]));
+        assert_eq!(
+            k_sum(&numbers, 6, numbers.iter().sum()),
+            Some(vec![0, 1, 2, 3, 4, 5])
+        );
+        assert_eq!(k_sum(&numbers, 7, 0), None);
+        assert_eq!(k_sum(&[-3, 7, 1, -1, 4], 3, 0), Some(vec![0, 3, 4]));
+    }
+
+    #[test]
+    fn test_k_sum_against_combinations() {
+        let mut random = Random::new(17);
+        let numbers = (0..40)
+            .map(|_| random.below(200) as i64 - 100)
+            .collect_vec();
+        for k in 1..=4 {
+            for target in -50..50 {
+                let expected = (0..numbers.len())
+                    .combinations(k)
+                    .any(|c| c.iter().map(|i| numbers[*i]).sum::<i64>() == target);
+                let found = k_sum(&numbers, k, target);
+                assert_eq!(found.is_some(), expected, "k {} target {}", k, target);
+                if let Some(picked) = found {
+                    assert_eq!(picked.iter().map(|i| numbers[*i]).sum::<i64>(), target);
+                    assert!(picked.iter().tuple_windows().all(|(a, b)| a < b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let mut numbers = (1..=25).collect_vec();
+        assert!(is_sum_of_two(&numbers, 26));
+        assert!(is_sum_of_two(&numbers, 49));
+        assert!(!is_sum_of_two(&numbers, 100));
+        assert!(!is_sum_of_two(&numbers, 50));
+
+        numbers.extend([26, 49, 100]);
+        assert_eq!(first_invalid(&numbers, 25), Some(27));
+        let numbers = (1..=25).chain([26, 50]).collect_vec();
+        assert_eq!(first_invalid(&numbers, 25), None);
+        // 1 and 4 have slid out of the window by the second 5
+        assert_eq!(first_invalid(&[1, 4, 5, 9, 5], 2), Some(4));
+        // equal values only count once
+        assert_eq!(first_invalid(&[3, 3, 6], 2), Some(2));
+    }
+
+    #[test]
+    fn test_contiguous_sum() {
+        let numbers = [35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117];
+        assert_eq!(contiguous_sum(&numbers, 127, 2), Some(2..6));
+        assert_eq!(contiguous_sum(&numbers, 47, 1), Some(4..5));
+        assert_eq!(contiguous_sum(&numbers, 47, 2), None);
+        assert_eq!(contiguous_sum(&[4, -2, 5, -1, 3], 3, 2), Some(1..3));
+        assert_eq!(contiguous_sum(&[4, -2, 5, -1, 3], 7, 3), Some(0..3));
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+// The elves' memory game, a Van Eck sequence with custom starting numbers:
+// after the starting numbers every turn says how many turns apart the last
+// number was said the last two times, or 0 if it was new.
+// https://oeis.org/A181391
+//
+// Apart from the starting numbers, every number said is the gap between two
+// turns and so smaller than the number of turns. The turn each number was
+// last said on goes in a flat table. Only numbers from the threshold up,
+// large starting numbers or turns long games rarely reach, fall back to a
+// hash map.
+
+const DEFAULT_THRESHOLD: usize = 1 << 25;
+
+#[derive(Debug, Clone)]
+pub struct MemoryGame {
+    starting: Vec<u32>,
+    // the turn (counting from 1) each number was last said on, 0 for never
+    last_seen: Vec<u32>,
+    last_seen_above: HashMap<u32, u32>,
+    threshold: usize,
+    turn: usize,
+    next: u32,
+}
+
+impl MemoryGame {
+    pub fn new(starting: &[u32]) -> Self {
+        Self {
+            starting: starting.to_vec(),
+            last_seen: vec![],
+            last_seen_above: HashMap::new(),
+            threshold: DEFAULT_THRESHOLD,
+            turn: 0,
+            next: 0,
+        }
+    }
+
+    // Numbers from `threshold` up are kept in the hash map. Can be changed
+    // in the middle of a game, the turns seen so far move over.
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        if self.last_seen.len() > threshold {
+            for (n, turn) in self.last_seen.drain(threshold..).enumerate() {
+                if turn != 0 {
+                    self.last_seen_above.insert((threshold + n) as u32, turn);
+                }
+            }
+        }
+        let below = self
+            .last_seen_above
+            .keys()
+            .filter(|n| (**n as usize) < threshold)
+            .copied()
+            .collect::<Vec<_>>();
+        for n in below {
+            let turn = self.last_seen_above.remove(&n).unwrap();
+            if n as usize >= self.last_seen.len() {
+                self.last_seen.resize(n as usize + 1, 0);
+            }
+            self.last_seen[n as usize] = turn;
+        }
+        self
+    }
+
+    // turns played so far
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    // stores the turn number was said on, returns the one before
+    fn say(&mut self, number: u32, turn: u32) -> u32 {
+        let n = number as usize;
+        if n >= self.threshold {
+            return self.last_seen_above.insert(number, turn).unwrap_or(0);
+        }
+        if n >= self.last_seen.len() {
+            let len = (n + 1).max(self.last_seen.len() * 2).min(self.threshold);
+            self.last_seen.resize(len, 0);
+        }
+        std::mem::replace(&mut self.last_seen[n], turn)
+    }
+
+    // The number said on turn (counting from 1), which must not have been
+    // played yet.
+    pub fn number_at(&mut self, turn: usize) -> u32 {
+        self.number_at_with_progress(turn, usize::MAX, |_, _| {})
+    }
+
+    // Same as `number_at`, calling progress with the turn and the number said
+    // every `every` turns.
+    pub fn number_at_with_progress(
+        &mut self,
+        turn: usize,
+        every: usize,
+        mut progress: impl FnMut(usize, u32),
+    ) -> u32 {
+        assert!(turn > self.turn, "turn {} was already played", turn);
+        let mut number = 0;
+        while self.turn < turn {
+            number = self.next().unwrap();
+            if self.turn.is_multiple_of(every) {
+                progress(self.turn, number);
+            }
+        }
+        number
+    }
+
+    // the numbers said from the next turn up to turn
+    pub fn prefix(&mut self, turn: usize) -> Vec<u32> {
+        let remaining = turn.saturating_sub(self.turn);
+        self.take(remaining).collect()
+    }
+}
+
+impl Iterator for MemoryGame {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.turn >= u32::MAX as usize {
+            return None;
+        }
+        self.turn += 1;
+        let number = match self.starting.get(self.turn - 1) {
+            Some(n) => *n,
+            None => self.next,
+        };
+        let turn = self.turn as u32;
+        let before = self.say(number, turn);
+        self.next = if before == 0 { 0 } else { turn - before };
+        Some(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix() {
+        let mut game = MemoryGame::new(&[0, 3, 6]);
+        assert_eq!(game.prefix(10), vec![0, 3, 6, 0, 3, 3, 1, 0, 4, 0]);
+        assert_eq!(game.turn(), 10);
+        assert_eq!(game.prefix(12), vec![2, 0]);
+        assert!(game.prefix(5).is_empty());
+    }
+
+    #[test]
+    fn test_number_at() {
+        let games = [
+            ([1, 3, 2], 1),
+            ([2, 1, 3], 10),
+            ([1, 2, 3], 27),
+            ([2, 3, 1], 78),
+            ([3, 2, 1], 438),
+            ([3, 1, 2], 1836),
+        ];
+        for (starting, expected) in games {
+            assert_eq!(MemoryGame::new(&starting).number_at(2020), expected);
+        }
+        // without starting numbers the game is the plain Van Eck sequence
+        assert_eq!(
+            MemoryGame::new(&[0]).prefix(8),
+            vec![0, 0, 1, 0, 2, 0, 2, 2]
+        );
+    }
+
+    #[test]
+    fn test_repeated_starting_numbers() {
+        let mut game = MemoryGame::new(&[1, 1, 5]);
+        assert_eq!(game.prefix(6), vec![1, 1, 5, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_threshold() {
+        let table = MemoryGame::new(&[7, 12, 1, 0, 16, 2]).prefix(50_000);
+        let hashed = MemoryGame::new(&[7, 12, 1, 0, 16, 2])
+            .threshold(100)
+            .prefix(50_000);
+        assert_eq!(table, hashed);
+        let only_hashed = MemoryGame::new(&[7, 12, 1, 0, 16, 2])
+            .threshold(0)
+            .prefix(50_000);
+        assert_eq!(table, only_hashed);
+
+        // lowering it mid-game keeps the history
+        let mut game = MemoryGame::new(&[7, 12, 1, 0, 16, 2]);
+        let mut switched = game.prefix(25_000);
+        game = game.threshold(10);
+        switched.extend(game.prefix(40_000));
+        // and raising it again too
+        game = game.threshold(1 << 20);
+        switched.extend(game.prefix(50_000));
+        assert_eq!(table, switched);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut reports = vec![];
+        let mut game = MemoryGame::new(&[0, 3, 6]);
+        let number = game.number_at_with_progress(10, 3, |turn, n| reports.push((turn, n)));
+        assert_eq!(number, 0);
+        assert_eq!(reports, vec![(3, 6), (6, 3), (9, 4)]);
+    }
+}
@@ -0,0 +1,209 @@
+use num_traits::{Num, One, Zero};
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+use super::egcd;
+use crate::algebra_helpers::Field;
+
+// Integers modulo M. Fulfills `Scalar`, so it can be used in points and
+// matrices. Division multiplies with the modular inverse and therefore only
+// works for divisors coprime to M (all non-zero ones if M is prime).
+// Reductions go through i128/u128, so any M up to u64::MAX works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(value: i64) -> Self {
+        Self::reduce(value as i128)
+    }
+
+    fn reduce(value: i128) -> Self {
+        Self(value.rem_euclid(M as i128) as u64)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut base = *self;
+        let mut result = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        if self.0 == 0 {
+            return None;
+        }
+        let (g, x, _) = egcd(self.0 as i128, M as i128);
+        if g != 1 {
+            return None;
+        }
+        Some(Self::reduce(x))
+    }
+}
+
+impl<const M: u64> Field for ModInt<M> {
+    fn inverse(&self) -> Option<Self> {
+        ModInt::inverse(self)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        Self(value % M)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const M: u64> Zero for ModInt<M> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const M: u64> One for ModInt<M> {
+    fn one() -> Self {
+        Self(1 % M)
+    }
+}
+
+impl<const M: u64> Num for ModInt<M> {
+    type FromStrRadixErr = std::num::ParseIntError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        Ok(Self::reduce(i128::from_str_radix(str, radix)?))
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(((self.0 as u128 * rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse().expect("divisor is not invertible modulo M")
+    }
+}
+
+impl<const M: u64> Rem for ModInt<M> {
+    type Output = Self;
+
+    // every division by an invertible element is exact
+    fn rem(self, rhs: Self) -> Self::Output {
+        rhs.inverse().expect("divisor is not invertible modulo M");
+        Self::zero()
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: u64> DivAssign for ModInt<M> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type M7 = ModInt<7>;
+    type MBig = ModInt<1_000_000_007>;
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(M7::new(5) + M7::new(4), M7::new(2));
+        assert_eq!(M7::new(2) - M7::new(5), M7::new(4));
+        assert_eq!(M7::new(-1), M7::new(6));
+        assert_eq!(M7::new(3) * M7::new(5), M7::new(1));
+        assert_eq!(M7::new(1) / M7::new(3), M7::new(5));
+        assert_eq!(-M7::new(0), M7::new(0));
+    }
+
+    #[test]
+    fn test_inverse_and_pow() {
+        assert_eq!(ModInt::<12>::new(4).inverse(), None);
+        assert_eq!(ModInt::<12>::new(5).inverse(), Some(ModInt::new(5)));
+        // Fermat's little theorem
+        assert_eq!(MBig::new(123456789).pow(1_000_000_006), MBig::one());
+        assert_eq!(MBig::new(2).pow(30).value(), (1 << 30) % 1_000_000_007);
+    }
+
+    #[test]
+    fn test_modulus_above_i64() {
+        // the largest prime below 2^64
+        type MHuge = ModInt<18_446_744_073_709_551_557>;
+        assert_eq!(MHuge::new(-1).value(), 18_446_744_073_709_551_556);
+        assert_eq!(
+            MHuge::new(i64::MIN).value(),
+            18_446_744_073_709_551_557 - (1 << 63)
+        );
+        let x = MHuge::from(u64::MAX - 100);
+        assert_eq!(x * x.inverse().unwrap(), MHuge::one());
+        assert_eq!(MHuge::from_str_radix("-2", 10), Ok(MHuge::new(-2)));
+    }
+}
@@ -0,0 +1,360 @@
+use num_traits::{Num, One, Signed, Zero};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::algebra_helpers::{Field, Scalar};
+
+// Exact fractions on top of any signed integer type, all of them fulfill
+// `Scalar`. `Rational<i64>` and `Rational<i128>` are `Copy`,
+// `Rational<BigInt>` never overflows.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRationalError<E> {
+    Invalid(E),
+    ZeroDenominator,
+}
+
+// always normalized: gcd(numer, denom) == 1 and denom > 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational<T> {
+    numer: T,
+    denom: T,
+}
+
+fn gcd<T: Clone + Signed>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while !b.is_zero() {
+        let r = a.clone() % b.clone();
+        a = b;
+        b = r;
+    }
+    a
+}
+
+impl<T: Clone + Signed> Rational<T> {
+    pub fn new(numer: T, denom: T) -> Self {
+        if denom.is_zero() {
+            panic!("denominator of a rational must not be zero");
+        }
+        let g = gcd(numer.clone(), denom.clone());
+        let (numer, denom) = (numer / g.clone(), denom / g);
+        if denom.is_negative() {
+            Self {
+                numer: -numer,
+                denom: -denom,
+            }
+        } else {
+            Self { numer, denom }
+        }
+    }
+
+    pub fn from_integer(n: T) -> Self {
+        Self {
+            numer: n,
+            denom: T::one(),
+        }
+    }
+
+    pub fn numer(&self) -> &T {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &T {
+        &self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom.is_one()
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.denom.clone(), self.numer.clone())
+    }
+
+    // rounds towards zero
+    pub fn trunc(&self) -> Self {
+        Self::from_integer(self.numer.clone() / self.denom.clone())
+    }
+
+    // rounds towards negative infinity
+    pub fn floor(&self) -> Self {
+        let t = self.trunc();
+        if self.numer.is_negative() && !self.is_integer() {
+            t - Self::one()
+        } else {
+            t
+        }
+    }
+}
+
+impl<T: Clone + Signed> From<T> for Rational<T> {
+    fn from(value: T) -> Self {
+        Self::from_integer(value)
+    }
+}
+
+impl<T: Clone + Signed + Ord> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross multiplication keeps the order
+        (self.numer.clone() * other.denom.clone()).cmp(&(other.numer.clone() * self.denom.clone()))
+    }
+}
+
+impl<T: Clone + Signed + Ord> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Clone + Signed + fmt::Display> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl<T: Clone + Signed> Num for Rational<T> {
+    type FromStrRadixErr = ParseRationalError<T::FromStrRadixErr>;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        let parse =
+            |s: &str| T::from_str_radix(s.trim(), radix).map_err(ParseRationalError::Invalid);
+        match str.split_once('/') {
+            Some((n, d)) => {
+                let d = parse(d)?;
+                if d.is_zero() {
+                    return Err(ParseRationalError::ZeroDenominator);
+                }
+                Ok(Self::new(parse(n)?, d))
+            }
+            None => Ok(Self::from_integer(parse(str)?)),
+        }
+    }
+}
+
+impl<T: Clone + Signed> FromStr for Rational<T> {
+    type Err = ParseRationalError<T::FromStrRadixErr>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+impl<T: Clone + Signed> Zero for Rational<T> {
+    fn zero() -> Self {
+        Self::from_integer(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+}
+
+impl<T: Clone + Signed> One for Rational<T> {
+    fn one() -> Self {
+        Self::from_integer(T::one())
+    }
+}
+
+impl<T: Clone + Signed> Add for Rational<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        // a/b + c/d = (a * d/g + c * b/g) / (b * d/g) with g = gcd(b, d)
+        let g = gcd(self.denom.clone(), rhs.denom.clone());
+        let b = self.denom.clone() / g.clone();
+        let d = rhs.denom / g;
+        Self::new(self.numer * d.clone() + rhs.numer * b, self.denom * d)
+    }
+}
+
+impl<T: Clone + Signed> Sub for Rational<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<T: Clone + Signed> Mul for Rational<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // cancel crosswise first to keep the intermediate values small
+        let g1 = gcd(self.numer.clone(), rhs.denom.clone());
+        let g2 = gcd(rhs.numer.clone(), self.denom.clone());
+        Self::new(
+            (self.numer / g1.clone()) * (rhs.numer / g2.clone()),
+            (self.denom / g2) * (rhs.denom / g1),
+        )
+    }
+}
+
+impl<T: Clone + Signed> Div for Rational<T> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.is_zero() {
+            panic!("attempt to divide by zero");
+        }
+        self * rhs.recip()
+    }
+}
+
+impl<T: Clone + Signed> Rem for Rational<T> {
+    type Output = Self;
+
+    // same sign convention as the primitive integers: a % b = a - b * trunc(a / b)
+    fn rem(self, rhs: Self) -> Self::Output {
+        let q = (self.clone() / rhs.clone()).trunc();
+        self - rhs * q
+    }
+}
+
+impl<T: Clone + Signed> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl<T: Clone + Signed> Signed for Rational<T> {
+    fn abs(&self) -> Self {
+        Self {
+            numer: self.numer.abs(),
+            denom: self.denom.clone(),
+        }
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        let d = self.clone() - other.clone();
+        if d.is_negative() {
+            Self::zero()
+        } else {
+            d
+        }
+    }
+
+    fn signum(&self) -> Self {
+        Self::from_integer(self.numer.signum())
+    }
+
+    fn is_positive(&self) -> bool {
+        self.numer.is_positive()
+    }
+
+    fn is_negative(&self) -> bool {
+        self.numer.is_negative()
+    }
+}
+
+impl<T: Clone + Signed> Field for Rational<T>
+where
+    Self: Scalar + Copy,
+{
+    fn inverse(&self) -> Option<Self> {
+        (!self.is_zero()).then(|| self.recip())
+    }
+}
+
+impl<T: Clone + Signed> AddAssign for Rational<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.clone() + rhs;
+    }
+}
+
+impl<T: Clone + Signed> SubAssign for Rational<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.clone() - rhs;
+    }
+}
+
+impl<T: Clone + Signed> MulAssign for Rational<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.clone() * rhs;
+    }
+}
+
+impl<T: Clone + Signed> DivAssign for Rational<T> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = self.clone() / rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra_helpers::{Point2, Scalar};
+    use crate::helpers::bigint::BigInt;
+
+    fn r(n: i64, d: i64) -> Rational<i64> {
+        Rational::new(n, d)
+    }
+
+    fn assert_scalar<T: Scalar>() {}
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(3, -6), r(-1, 2));
+        assert_eq!(*r(0, -5).denom(), 1);
+        assert_eq!(r(-4, -2), Rational::from_integer(2));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(r(1, 2) + r(1, 3), r(5, 6));
+        assert_eq!(r(1, 2) - r(1, 3), r(1, 6));
+        assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+        assert_eq!(r(2, 3) / r(4, 9), r(3, 2));
+        assert_eq!(r(7, 2) % r(1, 1), r(1, 2));
+        assert_eq!(r(-7, 2) % r(1, 1), r(-1, 2));
+        assert_eq!(r(-7, 2).floor(), r(-4, 1));
+        assert_eq!(r(-7, 2).trunc(), r(-3, 1));
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+    }
+
+    #[test]
+    fn test_display_and_parse() {
+        assert_eq!(r(-3, 4).to_string(), "-3/4");
+        assert_eq!(r(8, 4).to_string(), "2");
+        assert_eq!("6/-8".parse::<Rational<i64>>(), Ok(r(-3, 4)));
+        assert_eq!("5".parse::<Rational<i64>>(), Ok(r(5, 1)));
+        assert_eq!(
+            "1/0".parse::<Rational<i64>>(),
+            Err(ParseRationalError::ZeroDenominator)
+        );
+    }
+
+    #[test]
+    fn test_scalar() {
+        assert_scalar::<Rational<i64>>();
+        assert_scalar::<Rational<i128>>();
+        assert_scalar::<Rational<BigInt>>();
+        let p = Point2::new(r(1, 2), r(1, 3)) + Point2::new(r(1, 2), r(2, 3));
+        assert_eq!(p, Point2::new(r(1, 1), r(1, 1)));
+    }
+
+    #[test]
+    fn test_big_rational() {
+        // sum of 1/k^2 for k in 1..=40 overflows i64 denominators
+        let sum = (1..=40i64).fold(Rational::<BigInt>::zero(), |acc, k| {
+            acc + Rational::new(BigInt::one(), BigInt::from(k * k))
+        });
+        let approx = sum.numer().to_string().parse::<f64>().unwrap()
+            / sum.denom().to_string().parse::<f64>().unwrap();
+        assert!((approx - 1.6202).abs() < 1e-4);
+        assert!(sum.denom().to_string().len() > 30);
+    }
+}
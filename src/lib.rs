@@ -1,4 +1,5 @@
 #![feature(step_trait)]
+#![cfg_attr(test, feature(test))]
 /*
  * This file contains template code.
  * There is no need to edit this file unless you want to change template functionality.
@@ -16,6 +17,13 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 
 #[macro_export]
 macro_rules! solve {
+    // writes what `$exporter` makes of the input to a DOT file, only when
+    // the day runs with the debug flag (see `dot_requested`)
+    (dot, $day:expr, $exporter:ident, $input:expr) => {{
+        if advent_of_code::dot_requested() {
+            advent_of_code::write_dot($day, &$exporter($input).to_string());
+        }
+    }};
     ($part:expr, $solver:ident, $input:expr) => {{
         use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
         use std::fmt::Display;
@@ -52,6 +60,20 @@ pub fn read_file(folder: &str, day: u8) -> String {
     f.expect("could not open input file")
 }
 
+// Debug flag: run a day with `--dot` (cargo run --bin 07 -- --dot) or with
+// AOC_DOT set to export its parsed input for Graphviz.
+pub fn dot_requested() -> bool {
+    env::args().any(|a| a == "--dot") || env::var_os("AOC_DOT").is_some()
+}
+
+pub fn write_dot(day: u8, dot: &str) {
+    let dir = env::current_dir().unwrap().join("target").join("dot");
+    let filepath = dir.join(format!("{day:02}.dot"));
+    fs::create_dir_all(&dir).expect("could not create dot directory");
+    fs::write(&filepath, dot).expect("could not write dot file");
+    println!("🎄 Wrote {}", filepath.display());
+}
+
 fn parse_time(val: &str, postfix: &str) -> f64 {
     val.split(postfix).next().unwrap().parse().unwrap()
 }
@@ -190,8 +212,8 @@ pub mod aoc_cli {
 
         if output.status.success() {
             println!("---");
-            println!("🎄 Successfully wrote input to \"{}\".", &input_path);
-            println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+            println!("🎄 Successfully wrote input to \"{}\".", input_path);
+            println!("🎄 Successfully wrote puzzle to \"{}\".", puzzle_path);
             Ok(output)
         } else {
             Err(AocCliError::BadExitStatus(output))